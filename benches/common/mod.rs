@@ -0,0 +1,8 @@
+//! Helpers shared by the benchmarks.
+
+/// Repeats `pattern` until the line is `len` bytes long, or slightly shorter when that would split
+/// a character.
+pub fn repeat_to_length(pattern: &str, len: usize) -> String {
+    let line = pattern.repeat(len / pattern.len() + 1);
+    line[..line.floor_char_boundary(len)].to_string()
+}
@@ -0,0 +1,160 @@
+//! Benchmarks for the public parsing functions over realistic terminal output, such as cargo
+//! builds, pytest tracebacks and minified javascript, as well as a set of pathological inputs that
+//! have caused regex blowups in similar link detectors.
+
+mod common;
+
+use common::repeat_to_length;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use fetch_rs::{
+    OperatingSystem, detect_link_suffixes, detect_links, detect_links_batch,
+    get_link_at_position_in_line,
+};
+use std::hint::black_box;
+
+/// Length of the lines in the `long_lines` corpus and the pathological inputs.
+const LONG_LINE_LENGTH: usize = 10_000;
+
+/// Joins the lines of `text` with spaces into lines of roughly `len` bytes, similar to output that
+/// has been printed without newlines.
+fn join_to_length(text: &str, len: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for line in text.lines() {
+        let current = lines.last_mut().unwrap();
+        if current.len() >= len {
+            lines.push(line.to_string());
+        } else {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(line);
+        }
+    }
+    lines
+}
+
+fn corpora() -> Vec<(&'static str, Vec<String>)> {
+    let cargo_build = include_str!("corpus/cargo_build.txt");
+    let pytest = include_str!("corpus/pytest.txt");
+    let minified_js = include_str!("corpus/minified.js");
+    let lines = |text: &str| text.lines().map(str::to_string).collect::<Vec<_>>();
+
+    vec![
+        ("cargo_build", lines(cargo_build)),
+        ("pytest", lines(pytest)),
+        ("minified_js", lines(minified_js)),
+        // A `:verbose` log from neovim, mostly lines without any links
+        ("neovim_log", lines(include_str!("../out.log"))),
+        (
+            "long_lines",
+            join_to_length(
+                &[cargo_build, pytest, minified_js].concat(),
+                LONG_LINE_LENGTH,
+            ),
+        ),
+    ]
+}
+
+fn pathological_inputs() -> Vec<(&'static str, String)> {
+    let inputs = [
+        // Suffixes without any path in front of them
+        ("colons", "1:"),
+        ("parentheses", "(1,1)"),
+        ("line_words", "line 1, column 1 "),
+        // Opening brackets and quotes that never close
+        ("open_parentheses", "("),
+        ("open_brackets", "[a]("),
+        ("open_quotes", "\"'"),
+        // Many separators and path-like characters
+        ("separators", "/."),
+        ("drive_letters", "C:\\a"),
+        ("escaped_spaces", "a\\ "),
+        ("urls", "https://a/"),
+        ("multibyte", "é/ü:"),
+    ];
+
+    inputs
+        .into_iter()
+        .map(|(name, pattern)| (name, repeat_to_length(pattern, LONG_LINE_LENGTH)))
+        .collect()
+}
+
+fn bench_lines(c: &mut Criterion, group_name: &str, inputs: &[(&str, Vec<String>)]) {
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+    for (name, lines) in inputs {
+        let bytes: usize = lines.iter().map(String::len).sum();
+        group.throughput(Throughput::Bytes(bytes as u64));
+
+        group.bench_with_input(BenchmarkId::new("detect_links", name), lines, |b, lines| {
+            b.iter(|| {
+                for line in lines {
+                    black_box(detect_links(black_box(line), OperatingSystem::Linux));
+                }
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("detect_link_suffixes", name),
+            lines,
+            |b, lines| {
+                b.iter(|| {
+                    for line in lines {
+                        black_box(detect_link_suffixes(black_box(line)));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_link_at_position_in_line", name),
+            lines,
+            |b, lines| {
+                b.iter(|| {
+                    for line in lines {
+                        black_box(get_link_at_position_in_line(
+                            black_box(line),
+                            line.len() / 2,
+                            OperatingSystem::Linux,
+                        ));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_corpora(c: &mut Criterion) {
+    bench_lines(c, "corpora", &corpora());
+}
+
+fn bench_pathological(c: &mut Criterion) {
+    let inputs: Vec<(&str, Vec<String>)> = pathological_inputs()
+        .into_iter()
+        .map(|(name, line)| (name, vec![line]))
+        .collect();
+    bench_lines(c, "pathological", &inputs);
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let text = include_str!("../out.log");
+    let lines: Vec<&str> = text.lines().collect();
+
+    let mut group = c.benchmark_group("batch");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| detect_links(black_box(line), OperatingSystem::Linux))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("detect_links_batch", |b| {
+        b.iter(|| detect_links_batch(black_box(&lines), OperatingSystem::Linux))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_corpora, bench_pathological, bench_batch);
+criterion_main!(benches);
@@ -0,0 +1,7 @@
+function a0(e,t){return e.x?t[0]:{a:0,b:"k0",c:[0,1]}};function b1(e,t){return e.y?t[1]:{a:7,b:"k1",c:[1,2]}};function c2(e,t){return e.z?t[2]:{a:14,b:"k2",c:[2,3]}};function d3(e,t){return e.x?t[3]:{a:21,b:"k3",c:[3,4]}};function e4(e,t){return e.y?t[4]:{a:28,b:"k4",c:[4,5]}};function f5(e,t){return e.z?t[5]:{a:35,b:"k5",c:[5,6]}};function g6(e,t){return e.x?t[6]:{a:42,b:"k6",c:[6,7]}};function h7(e,t){return e.y?t[7]:{a:49,b:"k7",c:[7,8]}};function i8(e,t){return e.z?t[8]:{a:56,b:"k8",c:[8,9]}};function j9(e,t){return e.x?t[9]:{a:63,b:"k9",c:[9,10]}};function a10(e,t){return e.y?t[10]:{a:70,b:"k10",c:[10,11]}};function b11(e,t){return e.z?t[11]:{a:77,b:"k11",c:[11,12]}};function c12(e,t){return e.x?t[12]:{a:84,b:"k12",c:[12,13]}};function d13(e,t){return e.y?t[13]:{a:91,b:"k13",c:[13,14]}};function e14(e,t){return e.z?t[14]:{a:98,b:"k14",c:[14,15]}};function f15(e,t){return e.x?t[15]:{a:105,b:"k15",c:[15,16]}};function g16(e,t){return e.y?t[16]:{a:112,b:"k16",c:[16,17]}};function h17(e,t){return e.z?t[17]:{a:119,b:"k17",c:[17,18]}};function i18(e,t){return e.x?t[18]:{a:126,b:"k18",c:[18,19]}};function j19(e,t){return e.y?t[19]:{a:133,b:"k19",c:[19,20]}};function a20(e,t){return e.z?t[20]:{a:140,b:"k20",c:[20,21]}};function b21(e,t){return e.x?t[21]:{a:147,b:"k21",c:[21,22]}};function c22(e,t){return e.y?t[22]:{a:154,b:"k22",c:[22,23]}};function d23(e,t){return e.z?t[23]:{a:161,b:"k23",c:[23,24]}};function e24(e,t){return e.x?t[24]:{a:168,b:"k24",c:[24,25]}};function f25(e,t){return e.y?t[25]:{a:175,b:"k25",c:[25,26]}};function g26(e,t){return e.z?t[26]:{a:182,b:"k26",c:[26,27]}};function h27(e,t){return e.x?t[27]:{a:189,b:"k27",c:[27,28]}};function i28(e,t){return e.y?t[28]:{a:196,b:"k28",c:[28,29]}};function j29(e,t){return e.z?t[29]:{a:203,b:"k29",c:[29,30]}};function a30(e,t){return e.x?t[30]:{a:210,b:"k30",c:[30,31]}};function b31(e,t){return e.y?t[31]:{a:217,b:"k31",c:[31,32]}};function c32(e,t){return e.z?t[32]:{a:224,b:"k32",c:[32,33]}};function d33(e,t){return e.x?t[33]:{a:231,b:"k33",c:[33,34]}};function e34(e,t){return e.y?t[34]:{a:238,b:"k34",c:[34,35]}};function f35(e,t){return e.z?t[35]:{a:245,b:"k35",c:[35,36]}};function g36(e,t){return e.x?t[36]:{a:252,b:"k36",c:[36,37]}};function h37(e,t){return e.y?t[37]:{a:259,b:"k37",c:[37,38]}};function i38(e,t){return e.z?t[38]:{a:266,b:"k38",c:[38,39]}};function j39(e,t){return e.x?t[39]:{a:273,b:"k39",c:[39,40]}};function a40(e,t){return e.y?t[40]:{a:280,b:"k40",c:[40,41]}};function b41(e,t){return e.z?t[41]:{a:287,b:"k41",c:[41,42]}};function c42(e,t){return e.x?t[42]:{a:294,b:"k42",c:[42,43]}};function d43(e,t){return e.y?t[43]:{a:301,b:"k43",c:[43,44]}};function e44(e,t){return e.z?t[44]:{a:308,b:"k44",c:[44,45]}};function f45(e,t){return e.x?t[45]:{a:315,b:"k45",c:[45,46]}};function g46(e,t){return e.y?t[46]:{a:322,b:"k46",c:[46,47]}};function h47(e,t){return e.z?t[47]:{a:329,b:"k47",c:[47,48]}};function i48(e,t){return e.x?t[48]:{a:336,b:"k48",c:[48,49]}};function j49(e,t){return e.y?t[49]:{a:343,b:"k49",c:[49,50]}};function a50(e,t){return e.z?t[50]:{a:350,b:"k50",c:[50,51]}};function b51(e,t){return e.x?t[51]:{a:357,b:"k51",c:[51,52]}};function c52(e,t){return e.y?t[52]:{a:364,b:"k52",c:[52,53]}};function d53(e,t){return e.z?t[53]:{a:371,b:"k53",c:[53,54]}};function e54(e,t){return e.x?t[54]:{a:378,b:"k54",c:[54,55]}};function f55(e,t){return e.y?t[55]:{a:385,b:"k55",c:[55,56]}};function g56(e,t){return e.z?t[56]:{a:392,b:"k56",c:[56,57]}};function h57(e,t){return e.x?t[57]:{a:399,b:"k57",c:[57,58]}};function i58(e,t){return e.y?t[58]:{a:406,b:"k58",c:[58,59]}};function j59(e,t){return e.z?t[59]:{a:413,b:"k59",c:[59,60]}};function a60(e,t){return e.x?t[60]:{a:420,b:"k60",c:[60,61]}};function b61(e,t){return e.y?t[61]:{a:427,b:"k61",c:[61,62]}};function c62(e,t){return e.z?t[62]:{a:434,b:"k62",c:[62,63]}};function d63(e,t){return e.x?t[63]:{a:441,b:"k63",c:[63,64]}};function e64(e,t){return e.y?t[64]:{a:448,b:"k64",c:[64,65]}};function f65(e,t){return e.z?t[65]:{a:455,b:"k65",c:[65,66]}};function g66(e,t){return e.x?t[66]:{a:462,b:"k66",c:[66,67]}};function h67(e,t){return e.y?t[67]:{a:469,b:"k67",c:[67,68]}};function i68(e,t){return e.z?t[68]:{a:476,b:"k68",c:[68,69]}};function j69(e,t){return e.x?t[69]:{a:483,b:"k69",c:[69,70]}};function a70(e,t){return e.y?t[70]:{a:490,b:"k70",c:[70,71]}};function b71(e,t){return e.z?t[71]:{a:497,b:"k71",c:[71,72]}};function c72(e,t){return e.x?t[72]:{a:504,b:"k72",c:[72,73]}};function d73(e,t){return e.y?t[73]:{a:511,b:"k73",c:[73,74]}};function e74(e,t){return e.z?t[74]:{a:518,b:"k74",c:[74,75]}};function f75(e,t){return e.x?t[75]:{a:525,b:"k75",c:[75,76]}};function g76(e,t){return e.y?t[76]:{a:532,b:"k76",c:[76,77]}};function h77(e,t){return e.z?t[77]:{a:539,b:"k77",c:[77,78]}};function i78(e,t){return e.x?t[78]:{a:546,b:"k78",c:[78,79]}};function j79(e,t){return e.y?t[79]:{a:553,b:"k79",c:[79,80]}};function a80(e,t){return e.z?t[80]:{a:560,b:"k80",c:[80,81]}};function b81(e,t){return e.x?t[81]:{a:567,b:"k81",c:[81,82]}};function c82(e,t){return e.y?t[82]:{a:574,b:"k82",c:[82,83]}};function d83(e,t){return e.z?t[83]:{a:581,b:"k83",c:[83,84]}};function e84(e,t){return e.x?t[84]:{a:588,b:"k84",c:[84,85]}};function f85(e,t){return e.y?t[85]:{a:595,b:"k85",c:[85,86]}};function g86(e,t){return e.z?t[86]:{a:602,b:"k86",c:[86,87]}};function h87(e,t){return e.x?t[87]:{a:609,b:"k87",c:[87,88]}};function i88(e,t){return e.y?t[88]:{a:616,b:"k88",c:[88,89]}};function j89(e,t){return e.z?t[89]:{a:623,b:"k89",c:[89,90]}};function a90(e,t){return e.x?t[90]:{a:630,b:"k90",c:[90,91]}};function b91(e,t){return e.y?t[91]:{a:637,b:"k91",c:[91,92]}};function c92(e,t){return e.z?t[92]:{a:644,b:"k92",c:[92,93]}};function d93(e,t){return e.x?t[93]:{a:651,b:"k93",c:[93,94]}};function e94(e,t){return e.y?t[94]:{a:658,b:"k94",c:[94,95]}};function f95(e,t){return e.z?t[95]:{a:665,b:"k95",c:[95,96]}};function g96(e,t){return e.x?t[96]:{a:672,b:"k96",c:[96,97]}};function h97(e,t){return e.y?t[97]:{a:679,b:"k97",c:[97,98]}};function i98(e,t){return e.z?t[98]:{a:686,b:"k98",c:[98,99]}};function j99(e,t){return e.x?t[99]:{a:693,b:"k99",c:[99,100]}};function a100(e,t){return e.y?t[100]:{a:700,b:"k100",c:[100,101]}};function b101(e,t){return e.z?t[101]:{a:707,b:"k101",c:[101,102]}};function c102(e,t){return e.x?t[102]:{a:714,b:"k102",c:[102,103]}};function d103(e,t){return e.y?t[103]:{a:721,b:"k103",c:[103,104]}};function e104(e,t){return e.z?t[104]:{a:728,b:"k104",c:[104,105]}};function f105(e,t){return e.x?t[105]:{a:735,b:"k105",c:[105,106]}};function g106(e,t){return e.y?t[106]:{a:742,b:"k106",c:[106,107]}};function h107(e,t){return e.z?t[107]:{a:749,b:"k107",c:[107,108]}};function i108(e,t){return e.x?t[108]:{a:756,b:"k108",c:[108,109]}};function j109(e,t){return e.y?t[109]:{a:763,b:"k109",c:[109,110]}};function a110(e,t){return e.z?t[110]:{a:770,b:"k110",c:[110,111]}};function b111(e,t){return e.x?t[111]:{a:777,b:"k111",c:[111,112]}};function c112(e,t){return e.y?t[112]:{a:784,b:"k112",c:[112,113]}};function d113(e,t){return e.z?t[113]:{a:791,b:"k113",c:[113,114]}};function e114(e,t){return e.x?t[114]:{a:798,b:"k114",c:[114,115]}};function f115(e,t){return e.y?t[115]:{a:805,b:"k115",c:[115,116]}};function g116(e,t){return e.z?t[116]:{a:812,b:"k116",c:[116,117]}};function h117(e,t){return e.x?t[117]:{a:819,b:"k117",c:[117,118]}};function i118(e,t){return e.y?t[118]:{a:826,b:"k118",c:[118,119]}};function j119(e,t){return e.z?t[119]:{a:833,b:"k119",c:[119,120]}};function a120(e,t){return e.x?t[120]:{a:840,b:"k120",c:[120,121]}};function b121(e,t){return e.y?t[121]:{a:847,b:"k121",c:[121,122]}};function c122(e,t){return e.z?t[122]:{a:854,b:"k122",c:[122,123]}};function d123(e,t){return e.x?t[123]:{a:861,b:"k123",c:[123,124]}};function e124(e,t){return e.y?t[124]:{a:868,b:"k124",c:[124,125]}};function f125(e,t){return e.z?t[125]:{a:875,b:"k125",c:[125,126]}};function g126(e,t){return e.x?t[126]:{a:882,b:"k126",c:[126,127]}};function h127(e,t){return e.y?t[127]:{a:889,b:"k127",c:[127,128]}};function i128(e,t){return e.z?t[128]:{a:896,b:"k128",c:[128,129]}};function j129(e,t){return e.x?t[129]:{a:903,b:"k129",c:[129,130]}};function a130(e,t){return e.y?t[130]:{a:910,b:"k130",c:[130,131]}};function b131(e,t){return e.z?t[131]:{a:917,b:"k131",c:[131,132]}};function c132(e,t){return e.x?t[132]:{a:924,b:"k132",c:[132,133]}};function d133(e,t){return e.y?t[133]:{a:931,b:"k133",c:[133,134]}};function e134(e,t){return e.z?t[134]:{a:938,b:"k134",c:[134,135]}};function f135(e,t){return e.x?t[135]:{a:945,b:"k135",c:[135,136]}};function g136(e,t){return e.y?t[136]:{a:952,b:"k136",c:[136,137]}};function h137(e,t){return e.z?t[137]:{a:959,b:"k137",c:[137,138]}};function i138(e,t){return e.x?t[138]:{a:966,b:"k138",c:[138,139]}};function j139(e,t){return e.y?t[139]:{a:973,b:"k139",c:[139,140]}};function a140(e,t){return e.z?t[140]:{a:980,b:"k140",c:[140,141]}};function b141(e,t){return e.x?t[141]:{a:987,b:"k141",c:[141,142]}};function c142(e,t){return e.y?t[142]:{a:994,b:"k142",c:[142,143]}};function d143(e,t){return e.z?t[143]:{a:1001,b:"k143",c:[143,144]}};function e144(e,t){return e.x?t[144]:{a:1008,b:"k144",c:[144,145]}};function f145(e,t){return e.y?t[145]:{a:1015,b:"k145",c:[145,146]}};function g146(e,t){return e.z?t[146]:{a:1022,b:"k146",c:[146,147]}};function h147(e,t){return e.x?t[147]:{a:1029,b:"k147",c:[147,148]}};function i148(e,t){return e.y?t[148]:{a:1036,b:"k148",c:[148,149]}};function j149(e,t){return e.z?t[149]:{a:1043,b:"k149",c:[149,150]}};function a150(e,t){return e.x?t[150]:{a:1050,b:"k150",c:[150,151]}};function b151(e,t){return e.y?t[151]:{a:1057,b:"k151",c:[151,152]}};function c152(e,t){return e.z?t[152]:{a:1064,b:"k152",c:[152,153]}};function d153(e,t){return e.x?t[153]:{a:1071,b:"k153",c:[153,154]}};function e154(e,t){return e.y?t[154]:{a:1078,b:"k154",c:[154,155]}};function f155(e,t){return e.z?t[155]:{a:1085,b:"k155",c:[155,156]}};function g156(e,t){return e.x?t[156]:{a:1092,b:"k156",c:[156,157]}};function h157(e,t){return e.y?t[157]:{a:1099,b:"k157",c:[157,158]}};function i158(e,t){return e.z?t[158]:{a:1106,b:"k158",c:[158,159]}};function j159(e,t){return e.x?t[159]:{a:1113,b:"k159",c:[159,160]}};function a160(e,t){return e.y?t[160]:{a:1120,b:"k160",c:[160,161]}};function b161(e,t){return e.z?t[161]:{a:1127,b:"k161",c:[161,162]}};function c162(e,t){return e.x?t[162]:{a:1134,b:"k162",c:[162,163]}};function d163(e,t){return e.y?t[163]:{a:1141,b:"k163",c:[163,164]}};function e164(e,t){return e.z?t[164]:{a:1148,b:"k164",c:[164,165]}};function f165(e,t){return e.x?t[165]:{a:1155,b:"k165",c:[165,166]}};function g166(e,t){return e.y?t[166]:{a:1162,b:"k166",c:[166,167]}};function h167(e,t){return e.z?t[167]:{a:1169,b:"k167",c:[167,168]}};function i168(e,t){return e.x?t[168]:{a:1176,b:"k168",c:[168,169]}};function j169(e,t){return e.y?t[169]:{a:1183,b:"k169",c:[169,170]}};function a170(e,t){return e.z?t[170]:{a:1190,b:"k170",c:[170,171]}};function b171(e,t){return e.x?t[171]:{a:1197,b:"k171",c:[171,172]}};function c172(e,t){return e.y?t[172]:{a:1204,b:"k172",c:[172,173]}};function d173(e,t){return e.z?t[173]:{a:1211,b:"k173",c:[173,174]}};function e174(e,t){return e.x?t[174]:{a:1218,b:"k174",c:[174,175]}};function f175(e,t){return e.y?t[175]:{a:1225,b:"k175",c:[175,176]}};function g176(e,t){return e.z?t[176]:{a:1232,b:"k176",c:[176,177]}};function h177(e,t){return e.x?t[177]:{a:1239,b:"k177",c:[177,178]}};function i178(e,t){return e.y?t[178]:{a:1246,b:"k178",c:[178,179]}};function j179(e,t){return e.z?t[179]:{a:1253,b:"k179",c:[179,180]}};function a180(e,t){return e.x?t[180]:{a:1260,b:"k180",c:[180,181]}};function b181(e,t){return e.y?t[181]:{a:1267,b:"k181",c:[181,182]}};function c182(e,t){return e.z?t[182]:{a:1274,b:"k182",c:[182,183]}};function d183(e,t){return e.x?t[183]:{a:1281,b:"k183",c:[183,184]}};function e184(e,t){return e.y?t[184]:{a:1288,b:"k184",c:[184,185]}};function f185(e,t){return e.z?t[185]:{a:1295,b:"k185",c:[185,186]}};function g186(e,t){return e.x?t[186]:{a:1302,b:"k186",c:[186,187]}};function h187(e,t){return e.y?t[187]:{a:1309,b:"k187",c:[187,188]}};function i188(e,t){return e.z?t[188]:{a:1316,b:"k188",c:[188,189]}};function j189(e,t){return e.x?t[189]:{a:1323,b:"k189",c:[189,190]}};function a190(e,t){return e.y?t[190]:{a:1330,b:"k190",c:[190,191]}};function b191(e,t){return e.z?t[191]:{a:1337,b:"k191",c:[191,192]}};function c192(e,t){return e.x?t[192]:{a:1344,b:"k192",c:[192,193]}};function d193(e,t){return e.y?t[193]:{a:1351,b:"k193",c:[193,194]}};function e194(e,t){return e.z?t[194]:{a:1358,b:"k194",c:[194,195]}};function f195(e,t){return e.x?t[195]:{a:1365,b:"k195",c:[195,196]}};function g196(e,t){return e.y?t[196]:{a:1372,b:"k196",c:[196,197]}};function h197(e,t){return e.z?t[197]:{a:1379,b:"k197",c:[197,198]}};function i198(e,t){return e.x?t[198]:{a:1386,b:"k198",c:[198,199]}};function j199(e,t){return e.y?t[199]:{a:1393,b:"k199",c:[199,200]}};function a200(e,t){return e.z?t[200]:{a:1400,b:"k200",c:[200,201]}};function b201(e,t){return e.x?t[201]:{a:1407,b:"k201",c:[201,202]}};function c202(e,t){return e.y?t[202]:{a:1414,b:"k202",c:[202,203]}};function d203(e,t){return e.z?t[203]:{a:1421,b:"k203",c:[203,204]}};function e204(e,t){return e.x?t[204]:{a:1428,b:"k204",c:[204,205]}};function f205(e,t){return e.y?t[205]:{a:1435,b:"k205",c:[205,206]}};function g206(e,t){return e.z?t[206]:{a:1442,b:"k206",c:[206,207]}};function h207(e,t){return e.x?t[207]:{a:1449,b:"k207",c:[207,208]}};function i208(e,t){return e.y?t[208]:{a:1456,b:"k208",c:[208,209]}};function j209(e,t){return e.z?t[209]:{a:1463,b:"k209",c:[209,210]}};function a210(e,t){return e.x?t[210]:{a:1470,b:"k210",c:[210,211]}};function b211(e,t){return e.y?t[211]:{a:1477,b:"k211",c:[211,212]}};function c212(e,t){return e.z?t[212]:{a:1484,b:"k212",c:[212,213]}};function d213(e,t){return e.x?t[213]:{a:1491,b:"k213",c:[213,214]}};function e214(e,t){return e.y?t[214]:{a:1498,b:"k214",c:[214,215]}};function f215(e,t){return e.z?t[215]:{a:1505,b:"k215",c:[215,216]}};function g216(e,t){return e.x?t[216]:{a:1512,b:"k216",c:[216,217]}};function h217(e,t){return e.y?t[217]:{a:1519,b:"k217",c:[217,218]}};function i218(e,t){return e.z?t[218]:{a:1526,b:"k218",c:[218,219]}};function j219(e,t){return e.x?t[219]:{a:1533,b:"k219",c:[219,220]}};function a220(e,t){return e.y?t[220]:{a:1540,b:"k220",c:[220,221]}};function b221(e,t){return e.z?t[221]:{a:1547,b:"k221",c:[221,222]}};function c222(e,t){return e.x?t[222]:{a:1554,b:"k222",c:[222,223]}};function d223(e,t){return e.y?t[223]:{a:1561,b:"k223",c:[223,224]}};function e224(e,t){return e.z?t[224]:{a:1568,b:"k224",c:[224,225]}};function f225(e,t){return e.x?t[225]:{a:1575,b:"k225",c:[225,226]}};function g226(e,t){return e.y?t[226]:{a:1582,b:"k226",c:[226,227]}};function h227(e,t){return e.z?t[227]:{a:1589,b:"k227",c:[227,228]}};function i228(e,t){return e.x?t[228]:{a:1596,b:"k228",c:[228,229]}};function j229(e,t){return e.y?t[229]:{a:1603,b:"k229",c:[229,230]}};function a230(e,t){return e.z?t[230]:{a:1610,b:"k230",c:[230,231]}};function b231(e,t){return e.x?t[231]:{a:1617,b:"k231",c:[231,232]}};function c232(e,t){return e.y?t[232]:{a:1624,b:"k232",c:[232,233]}};function d233(e,t){return e.z?t[233]:{a:1631,b:"k233",c:[233,234]}};function e234(e,t){return e.x?t[234]:{a:1638,b:"k234",c:[234,235]}};function f235(e,t){return e.y?t[235]:{a:1645,b:"k235",c:[235,236]}};function g236(e,t){return e.z?t[236]:{a:1652,b:"k236",c:[236,237]}};function h237(e,t){return e.x?t[237]:{a:1659,b:"k237",c:[237,238]}};function i238(e,t){return e.y?t[238]:{a:1666,b:"k238",c:[238,239]}};function j239(e,t){return e.z?t[239]:{a:1673,b:"k239",c:[239,240]}};function a240(e,t){return e.x?t[240]:{a:1680,b:"k240",c:[240,241]}};function b241(e,t){return e.y?t[241]:{a:1687,b:"k241",c:[241,242]}};function c242(e,t){return e.z?t[242]:{a:1694,b:"k242",c:[242,243]}};function d243(e,t){return e.x?t[243]:{a:1701,b:"k243",c:[243,244]}};function e244(e,t){return e.y?t[244]:{a:1708,b:"k244",c:[244,245]}};function f245(e,t){return e.z?t[245]:{a:1715,b:"k245",c:[245,246]}};function g246(e,t){return e.x?t[246]:{a:1722,b:"k246",c:[246,247]}};function h247(e,t){return e.y?t[247]:{a:1729,b:"k247",c:[247,248]}};function i248(e,t){return e.z?t[248]:{a:1736,b:"k248",c:[248,249]}};function j249(e,t){return e.x?t[249]:{a:1743,b:"k249",c:[249,250]}};function a250(e,t){return e.y?t[250]:{a:1750,b:"k250",c:[250,251]}};function b251(e,t){return e.z?t[251]:{a:1757,b:"k251",c:[251,252]}};function c252(e,t){return e.x?t[252]:{a:1764,b:"k252",c:[252,253]}};function d253(e,t){return e.y?t[253]:{a:1771,b:"k253",c:[253,254]}};function e254(e,t){return e.z?t[254]:{a:1778,b:"k254",c:[254,255]}};function f255(e,t){return e.x?t[255]:{a:1785,b:"k255",c:[255,256]}};function g256(e,t){return e.y?t[256]:{a:1792,b:"k256",c:[256,257]}};function h257(e,t){return e.z?t[257]:{a:1799,b:"k257",c:[257,258]}};function i258(e,t){return e.x?t[258]:{a:1806,b:"k258",c:[258,259]}};function j259(e,t){return e.y?t[259]:{a:1813,b:"k259",c:[259,260]}};function a260(e,t){return e.z?t[260]:{a:1820,b:"k260",c:[260,261]}};function b261(e,t){return e.x?t[261]:{a:1827,b:"k261",c:[261,262]}};function c262(e,t){return e.y?t[262]:{a:1834,b:"k262",c:[262,263]}};function d263(e,t){return e.z?t[263]:{a:1841,b:"k263",c:[263,264]}};function e264(e,t){return e.x?t[264]:{a:1848,b:"k264",c:[264,265]}};function f265(e,t){return e.y?t[265]:{a:1855,b:"k265",c:[265,266]}};function g266(e,t){return e.z?t[266]:{a:1862,b:"k266",c:[266,267]}};function h267(e,t){return e.x?t[267]:{a:1869,b:"k267",c:[267,268]}};function i268(e,t){return e.y?t[268]:{a:1876,b:"k268",c:[268,269]}};function j269(e,t){return e.z?t[269]:{a:1883,b:"k269",c:[269,270]}};function a270(e,t){return e.x?t[270]:{a:1890,b:"k270",c:[270,271]}};function b271(e,t){return e.y?t[271]:{a:1897,b:"k271",c:[271,272]}};function c272(e,t){return e.z?t[272]:{a:1904,b:"k272",c:[272,273]}};function d273(e,t){return e.x?t[273]:{a:1911,b:"k273",c:[273,274]}};function e274(e,t){return e.y?t[274]:{a:1918,b:"k274",c:[274,275]}};function f275(e,t){return e.z?t[275]:{a:1925,b:"k275",c:[275,276]}};function g276(e,t){return e.x?t[276]:{a:1932,b:"k276",c:[276,277]}};function h277(e,t){return e.y?t[277]:{a:1939,b:"k277",c:[277,278]}};function i278(e,t){return e.z?t[278]:{a:1946,b:"k278",c:[278,279]}};function j279(e,t){return e.x?t[279]:{a:1953,b:"k279",c:[279,280]}};function a280(e,t){return e.y?t[280]:{a:1960,b:"k280",c:[280,281]}};function b281(e,t){return e.z?t[281]:{a:1967,b:"k281",c:[281,282]}};function c282(e,t){return e.x?t[282]:{a:1974,b:"k282",c:[282,283]}};function d283(e,t){return e.y?t[283]:{a:1981,b:"k283",c:[283,284]}};function e284(e,t){return e.z?t[284]:{a:1988,b:"k284",c:[284,285]}};function f285(e,t){return e.x?t[285]:{a:1995,b:"k285",c:[285,286]}};function g286(e,t){return e.y?t[286]:{a:2002,b:"k286",c:[286,287]}};function h287(e,t){return e.z?t[287]:{a:2009,b:"k287",c:[287,288]}};function i288(e,t){return e.x?t[288]:{a:2016,b:"k288",c:[288,289]}};function j289(e,t){return e.y?t[289]:{a:2023,b:"k289",c:[289,290]}};function a290(e,t){return e.z?t[290]:{a:2030,b:"k290",c:[290,291]}};function b291(e,t){return e.x?t[291]:{a:2037,b:"k291",c:[291,292]}};function c292(e,t){return e.y?t[292]:{a:2044,b:"k292",c:[292,293]}};function d293(e,t){return e.z?t[293]:{a:2051,b:"k293",c:[293,294]}};function e294(e,t){return e.x?t[294]:{a:2058,b:"k294",c:[294,295]}};function f295(e,t){return e.y?t[295]:{a:2065,b:"k295",c:[295,296]}};function g296(e,t){return e.z?t[296]:{a:2072,b:"k296",c:[296,297]}};function h297(e,t){return e.x?t[297]:{a:2079,b:"k297",c:[297,298]}};function i298(e,t){return e.y?t[298]:{a:2086,b:"k298",c:[298,299]}};function j299(e,t){return e.z?t[299]:{a:2093,b:"k299",c:[299,300]}};function a300(e,t){return e.x?t[300]:{a:2100,b:"k300",c:[300,301]}};function b301(e,t){return e.y?t[301]:{a:2107,b:"k301",c:[301,302]}};function c302(e,t){return e.z?t[302]:{a:2114,b:"k302",c:[302,303]}};function d303(e,t){return e.x?t[303]:{a:2121,b:"k303",c:[303,304]}};function e304(e,t){return e.y?t[304]:{a:2128,b:"k304",c:[304,305]}};function f305(e,t){return e.z?t[305]:{a:2135,b:"k305",c:[305,306]}};function g306(e,t){return e.x?t[306]:{a:2142,b:"k306",c:[306,307]}};function h307(e,t){return e.y?t[307]:{a:2149,b:"k307",c:[307,308]}};function i308(e,t){return e.z?t[308]:{a:2156,b:"k308",c:[308,309]}};function j309(e,t){return e.x?t[309]:{a:2163,b:"k309",c:[309,310]}};function a310(e,t){return e.y?t[310]:{a:2170,b:"k310",c:[310,311]}};function b311(e,t){return e.z?t[311]:{a:2177,b:"k311",c:[311,312]}};function c312(e,t){return e.x?t[312]:{a:2184,b:"k312",c:[312,313]}};function d313(e,t){return e.y?t[313]:{a:2191,b:"k313",c:[313,314]}};function e314(e,t){return e.z?t[314]:{a:2198,b:"k314",c:[314,315]}};function f315(e,t){return e.x?t[315]:{a:2205,b:"k315",c:[315,316]}};function g316(e,t){return e.y?t[316]:{a:2212,b:"k316",c:[316,317]}};function h317(e,t){return e.z?t[317]:{a:2219,b:"k317",c:[317,318]}};function i318(e,t){return e.x?t[318]:{a:2226,b:"k318",c:[318,319]}};function j319(e,t){return e.y?t[319]:{a:2233,b:"k319",c:[319,320]}};function a320(e,t){return e.z?t[320]:{a:2240,b:"k320",c:[320,321]}};function b321(e,t){return e.x?t[321]:{a:2247,b:"k321",c:[321,322]}};function c322(e,t){return e.y?t[322]:{a:2254,b:"k322",c:[322,323]}};function d323(e,t){return e.z?t[323]:{a:2261,b:"k323",c:[323,324]}};function e324(e,t){return e.x?t[324]:{a:2268,b:"k324",c:[324,325]}};function f325(e,t){return e.y?t[325]:{a:2275,b:"k325",c:[325,326]}};function g326(e,t){return e.z?t[326]:{a:2282,b:"k326",c:[326,327]}};function h327(e,t){return e.x?t[327]:{a:2289,b:"k327",c:[327,328]}};function i328(e,t){return e.y?t[328]:{a:2296,b:"k328",c:[328,329]}};function j329(e,t){return e.z?t[329]:{a:2303,b:"k329",c:[329,330]}};function a330(e,t){return e.x?t[330]:{a:2310,b:"k330",c:[330,331]}};function b331(e,t){return e.y?t[331]:{a:2317,b:"k331",c:[331,332]}};function c332(e,t){return e.z?t[332]:{a:2324,b:"k332",c:[332,333]}};function d333(e,t){return e.x?t[333]:{a:2331,b:"k333",c:[333,334]}};function e334(e,t){return e.y?t[334]:{a:2338,b:"k334",c:[334,335]}};function f335(e,t){return e.z?t[335]:{a:2345,b:"k335",c:[335,336]}};function g336(e,t){return e.x?t[336]:{a:2352,b:"k336",c:[336,337]}};function h337(e,t){return e.y?t[337]:{a:2359,b:"k337",c:[337,338]}};function i338(e,t){return e.z?t[338]:{a:2366,b:"k338",c:[338,339]}};function j339(e,t){return e.x?t[339]:{a:2373,b:"k339",c:[339,340]}};function a340(e,t){return e.y?t[340]:{a:2380,b:"k340",c:[340,341]}};function b341(e,t){return e.z?t[341]:{a:2387,b:"k341",c:[341,342]}};function c342(e,t){return e.x?t[342]:{a:2394,b:"k342",c:[342,343]}};function d343(e,t){return e.y?t[343]:{a:2401,b:"k343",c:[343,344]}};function e344(e,t){return e.z?t[344]:{a:2408,b:"k344",c:[344,345]}};function f345(e,t){return e.x?t[345]:{a:2415,b:"k345",c:[345,346]}};function g346(e,t){return e.y?t[346]:{a:2422,b:"k346",c:[346,347]}};function h347(e,t){return e.z?t[347]:{a:2429,b:"k347",c:[347,348]}};function i348(e,t){return e.x?t[348]:{a:2436,b:"k348",c:[348,349]}};function j349(e,t){return e.y?t[349]:{a:2443,b:"k349",c:[349,350]}};function a350(e,t){return e.z?t[350]:{a:2450,b:"k350",c:[350,351]}};function b351(e,t){return e.x?t[351]:{a:2457,b:"k351",c:[351,352]}};function c352(e,t){return e.y?t[352]:{a:2464,b:"k352",c:[352,353]}};function d353(e,t){return e.z?t[353]:{a:2471,b:"k353",c:[353,354]}};function e354(e,t){return e.x?t[354]:{a:2478,b:"k354",c:[354,355]}};function f355(e,t){return e.y?t[355]:{a:2485,b:"k355",c:[355,356]}};function g356(e,t){return e.z?t[356]:{a:2492,b:"k356",c:[356,357]}};function h357(e,t){return e.x?t[357]:{a:2499,b:"k357",c:[357,358]}};function i358(e,t){return e.y?t[358]:{a:2506,b:"k358",c:[358,359]}};function j359(e,t){return e.z?t[359]:{a:2513,b:"k359",c:[359,360]}};function a360(e,t){return e.x?t[360]:{a:2520,b:"k360",c:[360,361]}};function b361(e,t){return e.y?t[361]:{a:2527,b:"k361",c:[361,362]}};function c362(e,t){return e.z?t[362]:{a:2534,b:"k362",c:[362,363]}};function d363(e,t){return e.x?t[363]:{a:2541,b:"k363",c:[363,364]}};function e364(e,t){return e.y?t[364]:{a:2548,b:"k364",c:[364,365]}};function f365(e,t){return e.z?t[365]:{a:2555,b:"k365",c:[365,366]}};function g366(e,t){return e.x?t[366]:{a:2562,b:"k366",c:[366,367]}};function h367(e,t){return e.y?t[367]:{a:2569,b:"k367",c:[367,368]}};function i368(e,t){return e.z?t[368]:{a:2576,b:"k368",c:[368,369]}};function j369(e,t){return e.x?t[369]:{a:2583,b:"k369",c:[369,370]}};function a370(e,t){return e.y?t[370]:{a:2590,b:"k370",c:[370,371]}};function b371(e,t){return e.z?t[371]:{a:2597,b:"k371",c:[371,372]}};function c372(e,t){return e.x?t[372]:{a:2604,b:"k372",c:[372,373]}};function d373(e,t){return e.y?t[373]:{a:2611,b:"k373",c:[373,374]}};function e374(e,t){return e.z?t[374]:{a:2618,b:"k374",c:[374,375]}};function f375(e,t){return e.x?t[375]:{a:2625,b:"k375",c:[375,376]}};function g376(e,t){return e.y?t[376]:{a:2632,b:"k376",c:[376,377]}};function h377(e,t){return e.z?t[377]:{a:2639,b:"k377",c:[377,378]}};function i378(e,t){return e.x?t[378]:{a:2646,b:"k378",c:[378,379]}};function j379(e,t){return e.y?t[379]:{a:2653,b:"k379",c:[379,380]}};function a380(e,t){return e.z?t[380]:{a:2660,b:"k380",c:[380,381]}};function b381(e,t){return e.x?t[381]:{a:2667,b:"k381",c:[381,382]}};function c382(e,t){return e.y?t[382]:{a:2674,b:"k382",c:[382,383]}};function d383(e,t){return e.z?t[383]:{a:2681,b:"k383",c:[383,384]}};function e384(e,t){return e.x?t[384]:{a:2688,b:"k384",c:[384,385]}};function f385(e,t){return e.y?t[385]:{a:2695,b:"k385",c:[385,386]}};function g386(e,t){return e.z?t[386]:{a:2702,b:"k386",c:[386,387]}};function h387(e,t){return e.x?t[387]:{a:2709,b:"k387",c:[387,388]}};function i388(e,t){return e.y?t[388]:{a:2716,b:"k388",c:[388,389]}};function j389(e,t){return e.z?t[389]:{a:2723,b:"k389",c:[389,390]}};function a390(e,t){return e.x?t[390]:{a:2730,b:"k390",c:[390,391]}};function b391(e,t){return e.y?t[391]:{a:2737,b:"k391",c:[391,392]}};function c392(e,t){return e.z?t[392]:{a:2744,b:"k392",c:[392,393]}};function d393(e,t){return e.x?t[393]:{a:2751,b:"k393",c:[393,394]}};function e394(e,t){return e.y?t[394]:{a:2758,b:"k394",c:[394,395]}};function f395(e,t){return e.z?t[395]:{a:2765,b:"k395",c:[395,396]}};function g396(e,t){return e.x?t[396]:{a:2772,b:"k396",c:[396,397]}};function h397(e,t){return e.y?t[397]:{a:2779,b:"k397",c:[397,398]}};function i398(e,t){return e.z?t[398]:{a:2786,b:"k398",c:[398,399]}};function j399(e,t){return e.x?t[399]:{a:2793,b:"k399",c:[399,400]}}
+var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};var r=require("./dist/vendor.js"),o=require("../lib/util/index.js");module.exports={src:"src/components/App.tsx",map:"dist/bundle.js.map"};
+Error: Cannot read properties of undefined (reading 'map')
+    at render (webpack:///./src/components/List.tsx:42:17)
+    at Object.invoke (/home/user/app/dist/bundle.js:1:48213)
+    at processChild (/home/user/app/node_modules/react-dom/cjs/react-dom-server.node.development.js:3990:14)
+    at node:internal/process/task_queues:95:5
@@ -0,0 +1,51 @@
+//! Benchmarks for detecting links in long lines, these guard against regressions that make the
+//! time taken superlinear in the length of the line.
+
+mod common;
+
+use common::repeat_to_length;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use fetch_rs::{OperatingSystem, detect_links};
+use std::hint::black_box;
+
+const LINE_LENGTHS: [usize; 3] = [1_000, 100_000, 1_000_000];
+
+fn bench_long_lines(c: &mut Criterion) {
+    let inputs = [
+        // Compiler output with many links on one line
+        ("compiler_output", "error at src/foo/bar.rs:10:5 and "),
+        // Minified javascript object literals look like many suffixes without paths
+        ("minified_js", "{a:1,b:2,c:[3,4]},"),
+        // Deeply nested paths without whitespace
+        ("nested_path", "a/b/"),
+        // Backslashes caused catastrophic backtracking in VSCode #24795
+        ("backslashes", "\\\\"),
+        // Thousands of quoted links whose paths contain numbers that are detected as suffixes of
+        // their own, each of which drops the links detected within the quotes
+        ("quoted_links", "\"My 2019 files/foo.rs\", line 3 "),
+        // Quotes that never close
+        ("quotes", "\"a b/c ',1 "),
+        // A single word without any separators
+        ("no_separators", "abcdefgh"),
+        // Markup links, each of which is checked against the links found before it
+        ("markdown_links", "[a](b/c#L1) "),
+        ("autolinks", "<a/b> "),
+        ("rst_links", "`a <b/c>`_ "),
+    ];
+
+    let mut group = c.benchmark_group("detect_links");
+    group.sample_size(10);
+    for (name, pattern) in inputs {
+        for len in LINE_LENGTHS {
+            let line = repeat_to_length(pattern, len);
+            group.throughput(Throughput::Bytes(len as u64));
+            group.bench_with_input(BenchmarkId::new(name, len), &line, |b, line| {
+                b.iter(|| detect_links(black_box(line), OperatingSystem::Linux))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_long_lines);
+criterion_main!(benches);
@@ -14,28 +14,356 @@ local fetch_rs = load_fetch_rs()
 
 local M = {}
 
-M.get_links_from_line = function(line)
-	return fetch_rs.get_links_from_line(line)
+-- Every link has a confidence from 0 to 100 of pointing to a file, links below `min_confidence`
+-- are dropped
+M.get_links_from_line = function(line, min_confidence)
+	return fetch_rs.get_links_from_line(line, min_confidence or 0)
 end
 
-M.get_links_on_current_line = function()
-	return M.get_links_from_line(vim.api.nvim_get_current_line())
+-- Detects links in raw output that still contains ANSI escape sequences, such as colored compiler
+-- output captured in a log file. Each entry has the `link`, whose indices are byte offsets into the
+-- raw line, the raw `path_end`, `prefix_end` and `suffix_end` of its parts, which may contain
+-- escape sequences, and the decoded `target` of OSC 8 hyperlinks to files, which the visible text
+-- of the link may not show in full. Links below `min_confidence` are dropped.
+M.get_links_from_ansi_line = function(line, min_confidence)
+	return fetch_rs.get_links_from_ansi_line(line, min_confidence or 0)
 end
 
-M.get_link_at_position_in_line = function(line, pos)
-	return fetch_rs.get_link_at_position_in_line(line, pos)
+M.get_links_on_current_line = function(min_confidence)
+	return M.get_links_from_line(vim.api.nvim_get_current_line(), min_confidence)
 end
 
-M.get_link_under_cursor = function()
-	return M.get_link_at_position_in_line(vim.api.nvim_get_current_line(), vim.api.nvim_win_get_cursor(0)[2] + 1)
+-- Returns a list of links for each of the lines, in the same order as the lines
+M.get_links_from_lines = function(lines)
+	return fetch_rs.get_links_from_lines(lines)
 end
 
-M.open_link_under_cursor = function()
-	local link = M.get_link_under_cursor()
-	if link and vim.fn.filereadable(link.path.text) then
-		vim.cmd("edit " .. link.path.text)
-		vim.api.nvim_win_set_cursor(0, { link.suffix.row, link.suffix.col - 1 })
+M.get_links_in_buffer = function(bufnr)
+	return M.get_links_from_lines(vim.api.nvim_buf_get_lines(bufnr or 0, 0, -1, false))
+end
+
+-- How links are looked up from the cursor: "exact" for only the link containing it, "forward" to
+-- fall back to the next link on the line like `gf`, or "closest" for the nearest link in either
+-- direction, preferring the one after the cursor on a tie
+M.lookup = "exact"
+
+-- `pos` is a 1-based byte column and `lookup` defaults to "exact", see `M.lookup`
+M.get_link_at_position_in_line = function(line, pos, min_confidence, lookup)
+	return fetch_rs.get_link_at_position_in_line(line, pos, min_confidence or 0, lookup or "exact")
+end
+
+-- `lookup` defaults to `M.lookup`
+M.get_link_under_cursor = function(min_confidence, lookup)
+	return M.get_link_at_position_in_line(
+		vim.api.nvim_get_current_line(),
+		vim.api.nvim_win_get_cursor(0)[2] + 1,
+		min_confidence,
+		lookup or M.lookup
+	)
+end
+
+-- Returns the row and 1-based column of the next or previous link from the cursor, `direction` is
+-- "next" or "previous". `wrap` defaults to 'wrapscan'.
+M.get_adjacent_link_position = function(direction, wrap, min_confidence)
+	if wrap == nil then
+		wrap = vim.o.wrapscan
+	end
+	return fetch_rs.get_adjacent_link_position(direction, wrap, min_confidence or 0)
+end
+
+M.goto_link = function(direction, wrap, min_confidence)
+	-- The cursor of a terminal can't be moved in terminal mode
+	if vim.api.nvim_get_mode().mode == "t" then
+		vim.api.nvim_feedkeys(vim.api.nvim_replace_termcodes("<C-\\><C-n>", true, false, true), "nx", false)
+	end
+
+	local row, col = M.get_adjacent_link_position(direction, wrap, min_confidence)
+	if row then
+		vim.api.nvim_win_set_cursor(0, { row, col - 1 })
+	end
+	return row, col
+end
+
+M.goto_next_link = function(wrap, min_confidence)
+	return M.goto_link("next", wrap, min_confidence)
+end
+
+M.goto_previous_link = function(wrap, min_confidence)
+	return M.goto_link("previous", wrap, min_confidence)
+end
+
+-- Maps a location in compiled javascript, such as `dist/bundle.js:1:48213`, to the original
+-- source using the sibling `.map` file or the `sourceMappingURL` of the file. Returns a table with
+-- the `generated` and `original` locations and the original `name` of the symbol, or nil and an
+-- error when the file has no source map.
+M.resolve_source_map = function(path, row, col)
+	local ok, location = pcall(fetch_rs.resolve_source_map, vim.fn.fnamemodify(path, ":p"), row, col)
+	if not ok then
+		return nil, location
+	end
+	return location
+end
+
+-- Parses the Python tracebacks in the lines (defaults to the current buffer) into a list of
+-- tracebacks, each with its `frames`, `exception` and `chain` ("context" or "cause") to the one
+-- before it. Frames have a `path`, `suffix`, `function`, `source` line, `caret` columns of the
+-- source and the `line` they were printed on. The columns of the suffix are those of the carets in
+-- the file, and are only set when the file can be read from `base_dir` (defaults to the cwd), the
+-- directory the traceback was printed in.
+M.parse_python_traceback = function(lines, base_dir)
+	return fetch_rs.parse_python_traceback(
+		lines or vim.api.nvim_buf_get_lines(0, 0, -1, false),
+		base_dir or vim.fn.getcwd()
+	)
+end
+
+-- Parses the Rust panics in the lines (defaults to the current buffer) into a list of panics, each
+-- with its `thread`, `location`, `message` and the `frames` of its backtrace. Frames have an
+-- `index`, `symbol`, `location`, `origin` ("user", "std", "registry" or "unknown" for frames
+-- without a location) and the `line` they were printed on. Locations have a `path`, `suffix` and,
+-- for the standard library, the `local_path` of the file in the rust-src component when it's
+-- installed.
+M.parse_rust_backtrace = function(lines)
+	return fetch_rs.parse_rust_backtrace(lines or vim.api.nvim_buf_get_lines(0, 0, -1, false))
+end
+
+-- Parses the diagnostics in the output of `cargo build --message-format=json` (defaults to the
+-- current buffer). Diagnostics have a `link` with the exact range of the diagnostic in its `suffix`,
+-- a `severity` ("error", "warning", "info" or "hint"), `message`, `code`, `source`, `label` and
+-- `notes`. Paths are relative to the root of the workspace.
+M.parse_cargo_messages = function(lines)
+	return fetch_rs.parse_cargo_messages(lines or vim.api.nvim_buf_get_lines(0, 0, -1, false))
+end
+
+local quickfix_types = { error = "E", warning = "W", info = "I", hint = "N" }
+
+-- Sets the quickfix list to the diagnostics in the output of `cargo build --message-format=json`
+-- (defaults to the current buffer), with paths relative to `root` (defaults to the cwd)
+M.set_qflist_from_cargo_messages = function(lines, root)
+	root = root or vim.fn.getcwd()
+	local items = {}
+	for _, diagnostic in ipairs(M.parse_cargo_messages(lines)) do
+		local suffix = diagnostic.link.suffix
+		local text = diagnostic.message
+		if diagnostic.code then
+			text = text .. " [" .. diagnostic.code .. "]"
+		end
+		if diagnostic.label then
+			text = text .. ": " .. diagnostic.label
+		end
+		table.insert(items, {
+			filename = vim.fs.joinpath(root, diagnostic.link.path.text),
+			lnum = suffix.row,
+			col = suffix.col,
+			end_lnum = suffix.row_end or suffix.row,
+			end_col = suffix.col_end,
+			type = quickfix_types[diagnostic.severity],
+			text = text,
+		})
+	end
+	vim.fn.setqflist({}, " ", { title = "cargo", items = items })
+end
+
+local diagnostic_severities = {
+	error = vim.diagnostic.severity.ERROR,
+	warning = vim.diagnostic.severity.WARN,
+	info = vim.diagnostic.severity.INFO,
+	hint = vim.diagnostic.severity.HINT,
+}
+
+-- Loads the diagnostics of a SARIF log, LSP `textDocument/publishDiagnostics` notifications or
+-- cargo JSON messages into `vim.diagnostic` for the buffers of the files they reference. `format` is
+-- "sarif", "lsp" or "cargo" and detected from the content by default. Relative paths are relative to
+-- the cwd, and loading a file again replaces its diagnostics. Returns the number of diagnostics, or
+-- nil and an error.
+M.load_diagnostics_file = function(path, format)
+	path = vim.fn.fnamemodify(path, ":p")
+	local ok, diagnostics = pcall(fetch_rs.read_diagnostics_file, path, format)
+	if not ok then
+		return nil, diagnostics
+	end
+
+	local namespace = vim.api.nvim_create_namespace("fetch_diagnostics:" .. path)
+	vim.diagnostic.reset(namespace)
+
+	local buffers = {}
+	for _, diagnostic in ipairs(diagnostics) do
+		local suffix = diagnostic.link.suffix
+		local bufnr = vim.fn.bufadd(vim.fn.fnamemodify(diagnostic.link.path.text, ":p"))
+		buffers[bufnr] = buffers[bufnr] or {}
+		table.insert(buffers[bufnr], {
+			lnum = suffix.row - 1,
+			col = (suffix.col or 1) - 1,
+			end_lnum = (suffix.row_end or suffix.row) - 1,
+			-- The inclusive 1-based end column is the exclusive 0-based one
+			end_col = suffix.col_end,
+			severity = diagnostic_severities[diagnostic.severity],
+			message = diagnostic.message,
+			source = diagnostic.source,
+			code = diagnostic.code,
+		})
+	end
+	for bufnr, items in pairs(buffers) do
+		vim.diagnostic.set(namespace, bufnr, items)
+	end
+	return #diagnostics
+end
+
+M.get_permalinks_from_line = function(line)
+	return fetch_rs.get_permalinks_from_line(line)
+end
+
+-- Maps a forge permalink to the file in the local clone containing `dir` (defaults to the cwd)
+M.get_local_path_for_permalink = function(url, dir)
+	return fetch_rs.get_local_path_for_permalink(url, dir or vim.fn.getcwd())
+end
+
+-- Formats the location of the cursor or visual selection, `format` is one of "colon",
+-- "parentheses", "line" or "permalink"
+M.get_location_string = function(format)
+	return fetch_rs.get_location_string(format or "colon")
+end
+
+M.yank_location = function(format, register)
+	local location = M.get_location_string(format)
+	if location then
+		vim.fn.setreg(register or "+", location)
+	end
+	return location
+end
+
+-- Escaped spaces (eg. `My\ Documents`) are part of the link text but not of the file name
+local function link_path(link)
+	return (link.path.text:gsub("\\ ", " "))
+end
+
+-- The window links are opened in: "reuse" a window already showing the file or else the previous
+-- window, the "previous" window, a dedicated "split" or the "current" window
+M.open_policy = "reuse"
+
+-- Opens `path` and moves to the `row` and `col` of `suffix`, or selects its range. Returns false if
+-- the file doesn't exist.
+M.open_location = function(path, suffix, policy)
+	suffix = suffix or {}
+	return fetch_rs.open_location(
+		path,
+		suffix.row,
+		suffix.col,
+		suffix.row_end,
+		suffix.col_end,
+		policy or M.open_policy
+	)
+end
+
+-- Returns the links of the current buffer, or of all listed buffers, for pickers. Each entry has
+-- the `display` text, the resolved `path`, the `row` and `col` of the link and the `bufnr` and
+-- `line` it was found on.
+M.get_picker_entries = function(all_buffers, min_confidence)
+	return fetch_rs.get_picker_entries(all_buffers or false, min_confidence or 0)
+end
+
+M.pick_link = function(all_buffers, min_confidence)
+	vim.ui.select(M.get_picker_entries(all_buffers, min_confidence), {
+		prompt = "Links",
+		format_item = function(entry)
+			return entry.display
+		end,
+	}, function(entry)
+		if entry then
+			M.open_location(entry.path, entry)
+		end
+	end)
+end
+
+-- Labels every link in the window with a few keys from `alphabet` (defaults to the home row first)
+-- and opens the link whose label is typed. Any other key cancels.
+M.hint_links = function(alphabet, min_confidence)
+	local bufnr = vim.api.nvim_get_current_buf()
+	local hints = fetch_rs.show_link_hints(alphabet or "", min_confidence or 0)
+	if #hints == 0 then
+		return
+	end
+	vim.cmd.redraw()
+
+	local typed = ""
+	local chosen
+	while not chosen do
+		local ok, char = pcall(vim.fn.getcharstr)
+		if not ok then
+			break
+		end
+		typed = typed .. char
+
+		local matches = vim.tbl_filter(function(hint)
+			return vim.startswith(hint.label, typed)
+		end, hints)
+		if #matches == 0 then
+			break
+		elseif #matches == 1 and matches[1].label == typed then
+			chosen = matches[1]
+		end
+	end
+
+	fetch_rs.clear_link_hints(bufnr)
+	if chosen then
+		M.open_location(link_path(chosen.link), chosen.link.suffix)
+	end
+end
+
+-- `lookup` defaults to `M.lookup`, the link under the cursor functions below use it as well
+M.open_link_under_cursor = function(policy, min_confidence, lookup)
+	return fetch_rs.open_link_under_cursor(
+		policy or M.open_policy,
+		min_confidence or 0,
+		lookup or M.lookup
+	)
+end
+
+-- Opens the file or directory of the link under the cursor with `xdg-open`, `open` or `explorer`,
+-- which shows directories in the file manager
+M.open_link_under_cursor_with_system = function(min_confidence, lookup)
+	return fetch_rs.open_link_under_cursor_with_system(min_confidence or 0, lookup or M.lookup)
+end
+
+-- Returns "directory", "text" or "binary" for an existing path. Images, PDFs and other binary files
+-- are detected by their extension or first bytes.
+M.get_path_kind = function(path)
+	return fetch_rs.get_path_kind(vim.fn.fnamemodify(path, ":p"))
+end
+
+-- Shows `height` (defaults to 20) lines around the target of the link under the cursor in a
+-- floating window, with the rows of the target highlighted. The file is read without loading a
+-- buffer, binary files and files over 10MB aren't previewed.
+M.preview_link_under_cursor = function(height, min_confidence, lookup)
+	local ok, preview = pcall(
+		fetch_rs.get_preview_of_link_under_cursor,
+		height or 20,
+		min_confidence or 0,
+		lookup or M.lookup
+	)
+	if not ok then
+		vim.notify(preview, vim.log.levels.WARN)
+		return
+	elseif not preview then
+		return
+	end
+
+	local bufnr, winnr = vim.lsp.util.open_floating_preview(
+		preview.lines,
+		vim.filetype.match({ filename = preview.path }) or "",
+		{ focus_id = "fetch_preview", border = "rounded", title = vim.fn.fnamemodify(preview.path, ":~:.") }
+	)
+
+	if preview.row then
+		local namespace = vim.api.nvim_create_namespace("fetch_preview")
+		for row = preview.row, preview.row_end do
+			local index = row - preview.first_row
+			if index >= 0 and index < #preview.lines then
+				vim.api.nvim_buf_set_extmark(bufnr, namespace, index, 0, { line_hl_group = "Visual" })
+			end
+		end
 	end
+	return winnr
 end
 
 return M
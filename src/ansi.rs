@@ -0,0 +1,517 @@
+//! Detection of links in raw terminal output that still contains ANSI escape sequences, such as
+//! colored compiler output in `\x1b[1msrc/main.rs\x1b[0m:10:5`. The escape sequences are stripped
+//! before detection and the indices of the links are mapped back to the raw line.
+//!
+//! OSC 8 hyperlinks, such as those printed by `ls --hyperlink` and `rg --hyperlink-format`, carry an
+//! explicit `file://` target which is preferred over links detected in their visible text. The link
+//! covers the visible text while the target is returned alongside it.
+
+use crate::markup::link_suffix_from_fragment;
+use crate::offsets::OffsetMap;
+use crate::permalink::percent_decode;
+use crate::{
+    LinkPartialRange, MatchedRanges, OperatingSystem, ParsedLink, detect_links, link_range,
+};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, lua};
+use serde::Serialize;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// An OSC 8 hyperlink to a local file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// The target of the hyperlink, eg. `file://host/home/user/foo.rs`
+    pub uri: String,
+    /// The decoded path of the target, eg. `/home/user/foo.rs`
+    pub target: String,
+    /// The link covering the visible text of the hyperlink, its index is a byte offset into the raw
+    /// line. A line anchor in the target (`#L10`) isn't visible, so its suffix is an empty range at
+    /// the end of the visible text.
+    pub link: ParsedLink,
+}
+
+/// A link detected in a line containing ANSI escape sequences. The text of the parts of the link
+/// is the visible text while their indices are byte offsets into the raw line, so the raw ends of
+/// the parts are kept as well since a part containing escape sequences is longer in the raw line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnsiLink {
+    pub link: ParsedLink,
+    /// The decoded path of the target of an OSC 8 hyperlink, which is the file the link points to
+    /// rather than its visible text
+    pub target: Option<String>,
+    /// The exclusive end of the path in the raw line
+    pub path_end: usize,
+    pub prefix_end: Option<usize>,
+    pub suffix_end: Option<usize>,
+}
+
+impl AnsiLink {
+    /// Maps a link detected in the stripped line to the raw line.
+    fn new(link: ParsedLink, offsets: &OffsetMap) -> Self {
+        let end = |range: &LinkPartialRange| offsets.to_raw_end(range.index + range.text.len());
+
+        Self {
+            target: None,
+            path_end: end(&link.path),
+            prefix_end: link.prefix.as_ref().map(end),
+            suffix_end: link.suffix.as_ref().map(|suffix| end(&suffix.suffix)),
+            link: offsets.map_link(link),
+        }
+    }
+}
+
+impl ToObject for AnsiLink {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for AnsiLink {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// Detects the links in a line containing ANSI escape sequences, dropping those with a
+/// [`ParsedLink::confidence`] below `min_confidence`.
+pub fn detect_links_ansi_with_min_confidence(
+    line: &str,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> Vec<AnsiLink> {
+    detect_links_ansi(line, os)
+        .into_iter()
+        .filter(|link| link.link.confidence >= min_confidence)
+        .collect()
+}
+
+/// A line with its escape sequences removed.
+struct StrippedLine {
+    text: String,
+    offsets: OffsetMap,
+    /// The targets of the OSC 8 hyperlinks along with the index of the target in the raw line and
+    /// the range of the visible text in `text`
+    hyperlinks: Vec<(String, usize, (usize, usize))>,
+}
+
+/// Detects the links in a line containing ANSI escape sequences.
+pub fn detect_links_ansi(line: &str, os: OperatingSystem) -> Vec<AnsiLink> {
+    if !line.as_bytes().contains(&ESC) {
+        let offsets = OffsetMap::default();
+        return detect_links(line, os)
+            .into_iter()
+            .map(|link| AnsiLink::new(link, &offsets))
+            .collect();
+    }
+
+    let stripped = strip_escape_sequences(line);
+    // The hyperlinks along with the range of their visible text in the stripped line and the raw
+    // end of their suffix, a line anchor in the target is an empty suffix
+    let mut hyperlinks: Vec<_> = to_hyperlinks(&stripped, os)
+        .into_iter()
+        .map(|(hyperlink, range)| {
+            let suffix_end = hyperlink.link.suffix.as_ref().map(|s| s.suffix.index);
+            (hyperlink, range, suffix_end)
+        })
+        .collect();
+    let hyperlink_ranges =
+        MatchedRanges::new(hyperlinks.iter().map(|(_, range, _)| *range).collect());
+
+    let mut results: Vec<AnsiLink> = Vec::new();
+    for link in detect_links(&stripped.text, os) {
+        let (start, end) = link_range(&link);
+        if !hyperlink_ranges.overlaps(start, end) {
+            results.push(AnsiLink::new(link, &stripped.offsets));
+            continue;
+        }
+
+        // Tools such as ripgrep only link the path, so the line and column printed after the
+        // visible text are kept when the target has none of its own
+        let hyperlink = hyperlinks.iter_mut().find(|(_, (s, e), _)| {
+            link.path.index >= *s && link.path.index + link.path.text.len() <= *e
+        });
+        if let Some((hyperlink, _, suffix_end)) = hyperlink
+            && hyperlink.link.suffix.is_none()
+            && let Some(mut suffix) = link.suffix
+        {
+            *suffix_end = Some(
+                stripped
+                    .offsets
+                    .to_raw_end(suffix.suffix.index + suffix.suffix.text.len()),
+            );
+            suffix.suffix.index = stripped.offsets.to_raw(suffix.suffix.index);
+            hyperlink.link.suffix = Some(suffix);
+        }
+    }
+
+    results.extend(
+        hyperlinks
+            .into_iter()
+            .map(|(hyperlink, (_, end), suffix_end)| AnsiLink {
+                link: hyperlink.link,
+                target: Some(hyperlink.target),
+                path_end: stripped.offsets.to_raw_end(end),
+                prefix_end: None,
+                suffix_end,
+            }),
+    );
+    results.sort_by_key(|link| link.link.path.index);
+    results
+}
+
+/// Detects the OSC 8 hyperlinks to local files in the raw line.
+pub fn detect_hyperlinks(line: &str, os: OperatingSystem) -> Vec<Hyperlink> {
+    to_hyperlinks(&strip_escape_sequences(line), os)
+        .into_iter()
+        .map(|(hyperlink, _)| hyperlink)
+        .collect()
+}
+
+/// Removes all CSI sequences (colors and cursor movement), OSC sequences (titles and hyperlinks)
+/// and other escape sequences from the line.
+pub fn strip_ansi(line: &str) -> String {
+    strip_escape_sequences(line).text
+}
+
+/// Removes the escape sequences from the line, recording the offsets of the remaining segments in
+/// the raw line and the hyperlinks that were opened and closed along the way.
+fn strip_escape_sequences(line: &str) -> StrippedLine {
+    let bytes = line.as_bytes();
+    let mut text = String::with_capacity(line.len());
+    let mut offsets = OffsetMap::default();
+    let mut hyperlinks = Vec::new();
+    let mut open_hyperlink: Option<(&str, usize, usize)> = None;
+    let mut segment_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != ESC {
+            i += 1;
+            continue;
+        }
+
+        if segment_start < i {
+            offsets.push(text.len(), segment_start, i - segment_start);
+            text.push_str(&line[segment_start..i]);
+        }
+
+        let end = escape_sequence_end(bytes, i);
+        if let Some((uri, uri_index)) = hyperlink_uri(line, i, end) {
+            // A hyperlink is closed by an empty target or by opening the next one
+            if let Some((uri, uri_index, start)) = open_hyperlink.take()
+                && start < text.len()
+            {
+                hyperlinks.push((uri.to_string(), uri_index, (start, text.len())));
+            }
+            if !uri.is_empty() {
+                open_hyperlink = Some((uri, uri_index, text.len()));
+            }
+        }
+        i = end;
+        segment_start = i;
+    }
+
+    if segment_start < bytes.len() {
+        offsets.push(text.len(), segment_start, bytes.len() - segment_start);
+        text.push_str(&line[segment_start..]);
+    }
+
+    // Hyperlinks that are still open at the end of the line continue on the next line
+    if let Some((uri, uri_index, start)) = open_hyperlink
+        && start < text.len()
+    {
+        hyperlinks.push((uri.to_string(), uri_index, (start, text.len())));
+    }
+
+    StrippedLine {
+        text,
+        offsets,
+        hyperlinks,
+    }
+}
+
+/// Returns the target of the OSC 8 sequence in `line[start..end]` along with its index, the target
+/// is empty for the sequence closing a hyperlink. The sequence is `ESC ] 8 ; params ; target ST`.
+fn hyperlink_uri(line: &str, start: usize, end: usize) -> Option<(&str, usize)> {
+    let content_start = start + "\x1b]8;".len();
+    let sequence = line.get(start..end)?.strip_prefix("\x1b]8;")?;
+    let content = sequence
+        .strip_suffix('\x07')
+        .or_else(|| sequence.strip_suffix("\x1b\\"))
+        .unwrap_or(sequence);
+    let (params, uri) = content.split_once(';')?;
+    Some((uri, content_start + params.len() + 1))
+}
+
+/// Converts the hyperlinks with `file://` targets to links, returning them along with the range of
+/// their visible text in the stripped line.
+fn to_hyperlinks(stripped: &StrippedLine, os: OperatingSystem) -> Vec<(Hyperlink, (usize, usize))> {
+    stripped
+        .hyperlinks
+        .iter()
+        .filter_map(|(uri, uri_index, (start, end))| {
+            let (target, fragment) = file_uri_target(uri, os)?;
+            let index = stripped.offsets.to_raw(*start);
+            let end_index = stripped.offsets.to_raw_end(*end);
+            let suffix = fragment
+                .and_then(|fragment| {
+                    let fragment_index = uri_index + uri.len() - fragment.len();
+                    link_suffix_from_fragment(fragment, fragment_index)
+                })
+                .map(|mut suffix| {
+                    suffix.suffix = LinkPartialRange {
+                        index: end_index,
+                        text: String::new(),
+                    };
+                    suffix
+                });
+
+            Some((
+                Hyperlink {
+                    uri: uri.clone(),
+                    target,
+                    link: ParsedLink {
+                        path: LinkPartialRange {
+                            index,
+                            text: stripped.text[*start..*end].to_string(),
+                        },
+                        prefix: None,
+                        suffix,
+                        confidence: 100,
+                    },
+                },
+                (*start, *end),
+            ))
+        })
+        .collect()
+}
+
+/// Returns the decoded path of a `file://host/path#fragment` target along with its fragment. The
+/// host is ignored since terminals are expected to only link files on the machine they run on.
+fn file_uri_target(uri: &str, os: OperatingSystem) -> Option<(String, Option<&str>)> {
+    let rest = uri.strip_prefix("file://")?;
+    let path_start = rest.find('/')?;
+    let (path, fragment) = match rest.find('#') {
+        Some(hash) if hash > path_start => (&rest[path_start..hash], Some(&rest[hash..])),
+        _ => (&rest[path_start..], None),
+    };
+    let path = path.split('?').next().unwrap_or(path);
+
+    let mut path = percent_decode(path);
+    // `file:///C:/foo` is the path `C:/foo` on Windows
+    if os == OperatingSystem::Windows
+        && path.as_bytes().get(2) == Some(&b':')
+        && path.as_bytes()[1].is_ascii_alphabetic()
+    {
+        path.remove(0);
+    }
+    Some((path, fragment))
+}
+
+/// Returns the index after the escape sequence starting at `start`. Sequences that aren't
+/// terminated extend to the end of the line. Only ASCII bytes are consumed apart from the contents
+/// of string sequences, so the returned index is always a char boundary.
+fn escape_sequence_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    match bytes.get(i) {
+        // CSI: parameter bytes, intermediate bytes and a final byte, eg. `\x1b[1;31m`
+        Some(b'[') => {
+            i += 1;
+            while i < bytes.len() && (0x20..=0x3f).contains(&bytes[i]) {
+                i += 1;
+            }
+            if i < bytes.len() && (0x40..=0x7e).contains(&bytes[i]) {
+                i += 1;
+            }
+            i
+        }
+        // OSC, DCS, SOS, PM and APC: strings terminated by BEL or ST (`\x1b\\`)
+        Some(b']' | b'P' | b'X' | b'^' | b'_') => string_sequence_end(bytes, i + 1),
+        // Other sequences: intermediate bytes and a final byte, eg. `\x1b(B` or `\x1b7`
+        Some(_) => {
+            while i < bytes.len() && (0x20..=0x2f).contains(&bytes[i]) {
+                i += 1;
+            }
+            if i < bytes.len() && (0x30..=0x7e).contains(&bytes[i]) {
+                i += 1;
+            }
+            i
+        }
+        None => i,
+    }
+}
+
+/// Returns the index after the BEL or ST terminating the string that starts at `start`.
+fn string_sequence_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            BEL => return i + 1,
+            ESC if bytes.get(i + 1) == Some(&b'\\') => return i + 2,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ansi::{
+        detect_hyperlinks, detect_links_ansi, detect_links_ansi_with_min_confidence, strip_ansi,
+    };
+    use crate::{LinkPartialRange, OperatingSystem, link_range};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    pub fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b]0;title\x07\x1b[1;31merror\x1b[0m: \x1b(Bdone\x1b[K"),
+            "error: done"
+        );
+        assert_eq!(strip_ansi("no escapes"), "no escapes");
+        assert_eq!(strip_ansi("unterminated \x1b]0;title"), "unterminated ");
+    }
+
+    #[test]
+    pub fn test_bold_path_with_suffix() {
+        let line = "\x1b[1mfoo.rs\x1b[0m:10:5";
+        let results = detect_links_ansi(line, OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 1);
+        let link = &results[0].link;
+        assert_eq!(link.path.text, "foo.rs");
+        assert_eq!(&line[link.path.index..link.path.index + 6], "foo.rs");
+        assert_eq!(results[0].target, None);
+
+        let suffix = link.suffix.as_ref().unwrap();
+        assert_eq!((suffix.row, suffix.col), (Some(10), Some(5)));
+        assert_eq!(&line[suffix.suffix.index..], ":10:5");
+    }
+
+    #[test]
+    pub fn test_escape_sequences_inside_link() {
+        let line = "src/\x1b[1mmain\x1b[0m.rs\x1b[2m:3\x1b[0m done";
+        let results = detect_links_ansi(line, OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 1);
+        let link = &results[0];
+        assert_eq!(link.link.path.text, "src/main.rs");
+        assert_eq!(
+            &line[link.link.path.index..link.path_end],
+            "src/\x1b[1mmain\x1b[0m.rs"
+        );
+
+        let suffix = link.link.suffix.as_ref().unwrap();
+        assert_eq!(&line[suffix.suffix.index..link.suffix_end.unwrap()], ":3");
+    }
+
+    #[test]
+    pub fn test_colored_cargo_output() {
+        let line = "\x1b[0m\x1b[1m\x1b[38;5;12m  --> \x1b[0m\x1b[0msrc/lib.rs:412:9\x1b[0m";
+        let results = detect_links_ansi(line, OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 1);
+        let link = &results[0].link;
+        assert_eq!(link.path.text, "src/lib.rs");
+        assert_eq!(&line[link.path.index..][..10], "src/lib.rs");
+        assert_eq!(link.suffix.as_ref().unwrap().row, Some(412));
+    }
+
+    #[test]
+    pub fn test_hyperlink_with_fragment() {
+        let line = "see \x1b]8;id=1;file://host/home/user/my%20file.rs#L10\x1b\\\x1b[1mmy file\x1b[0m\x1b]8;;\x1b\\ and https://example.com";
+        let results = detect_hyperlinks(line, OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 1);
+        let hyperlink = &results[0];
+        assert_eq!(hyperlink.uri, "file://host/home/user/my%20file.rs#L10");
+        assert_eq!(hyperlink.target, "/home/user/my file.rs");
+        assert_eq!(
+            hyperlink.link.path,
+            LinkPartialRange {
+                index: line.find("my file").unwrap(),
+                text: "my file".to_string(),
+            }
+        );
+
+        let suffix = hyperlink.link.suffix.as_ref().unwrap();
+        assert_eq!(suffix.row, Some(10));
+        let visible_end = line.find("my file").unwrap() + "my file".len();
+        assert_eq!(
+            link_range(&hyperlink.link),
+            (line.find("my file").unwrap(), visible_end)
+        );
+    }
+
+    #[test]
+    pub fn test_hyperlink_range_is_visible_text() {
+        let line = "\x1b]8;;file:///home/user/foo.txt\x1b\\foo.txt\x1b]8;;\x1b\\ done";
+        let results = detect_links_ansi(line, OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 1);
+        let start = line.rfind("foo.txt").unwrap();
+        assert_eq!(
+            link_range(&results[0].link),
+            (start, start + "foo.txt".len())
+        );
+        assert_eq!(results[0].path_end, start + "foo.txt".len());
+        assert_eq!(results[0].link.path.text, "foo.txt");
+        assert_eq!(results[0].target.as_deref(), Some("/home/user/foo.txt"));
+    }
+
+    #[test]
+    pub fn test_hyperlink_preferred_over_heuristic() {
+        // ripgrep with `--hyperlink-format default` only links the path
+        let line = "\x1b]8;;file:///home/user/src/main.rs\x07src/main.rs\x1b]8;;\x07:10:5:fn main() {} in ./other.rs";
+        let results = detect_links_ansi(line, OperatingSystem::Linux);
+
+        let paths: Vec<&str> = results.iter().map(|l| l.link.path.text.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs", "./other.rs"]);
+        let targets: Vec<Option<&str>> = results.iter().map(|l| l.target.as_deref()).collect();
+        assert_eq!(targets, vec![Some("/home/user/src/main.rs"), None]);
+
+        let suffix = results[0].link.suffix.as_ref().unwrap();
+        assert_eq!((suffix.row, suffix.col), (Some(10), Some(5)));
+        assert_eq!(&line[suffix.suffix.index..][..5], ":10:5");
+        let start = line.find("src/main.rs\x1b").unwrap();
+        assert_eq!(
+            link_range(&results[0].link),
+            (start, line.find(":10:5").unwrap() + 5)
+        );
+        assert_eq!(results[0].path_end, start + "src/main.rs".len());
+        assert_eq!(results[0].suffix_end, Some(line.find(":10:5").unwrap() + 5));
+    }
+
+    #[test]
+    pub fn test_min_confidence() {
+        let line = "\x1b[1mand/or\x1b[0m in \x1b[1m./foo.rs\x1b[0m:3";
+        let paths = |min_confidence| {
+            detect_links_ansi_with_min_confidence(line, OperatingSystem::Linux, min_confidence)
+                .into_iter()
+                .map(|l| l.link.path.text)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(paths(0), vec!["and/or", "./foo.rs"]);
+        assert_eq!(paths(50), vec!["./foo.rs"]);
+    }
+
+    #[test]
+    pub fn test_hyperlink_windows_drive() {
+        let line = "\x1b]8;;file:///C:/Users/me/foo.txt\x1b\\foo.txt\x1b]8;;\x1b\\";
+
+        let results = detect_links_ansi(line, OperatingSystem::Windows);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].target.as_deref(), Some("C:/Users/me/foo.txt"));
+
+        let results = detect_links_ansi(line, OperatingSystem::Linux);
+        assert_eq!(results[0].target.as_deref(), Some("/C:/Users/me/foo.txt"));
+    }
+}
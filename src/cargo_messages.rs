@@ -0,0 +1,270 @@
+//! Ingestion of the JSON messages printed by `cargo build --message-format=json` and
+//! `rustc --error-format=json`, whose spans give the exact ranges of diagnostics instead of the
+//! `--> src/main.rs:10:5` lines of the rendered output.
+
+use crate::diagnostics::{Diagnostic, Severity, location_link, range_location};
+use crate::format::Location;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// A line of `cargo --message-format=json`, or a diagnostic printed by rustc itself.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawLine {
+    Cargo {
+        reason: String,
+        message: Option<RawDiagnostic>,
+    },
+    Rustc(RawDiagnostic),
+}
+
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    code: Option<RawCode>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<RawSpan>,
+    #[serde(default)]
+    children: Vec<RawDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RawCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    file_name: String,
+    line_start: u32,
+    line_end: u32,
+    column_start: u32,
+    /// Columns are 1-based and the end is exclusive
+    column_end: u32,
+    is_primary: bool,
+    label: Option<String>,
+    expansion: Option<Box<RawExpansion>>,
+}
+
+#[derive(Deserialize)]
+struct RawExpansion {
+    span: RawSpan,
+}
+
+fn severity(level: &str) -> Severity {
+    match level {
+        "error" | "error: internal compiler error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "help" => Severity::Hint,
+        _ => Severity::Info,
+    }
+}
+
+/// Returns the location of the span, following macro expansions out of spans in the definitions
+/// of external macros, such as `<::core::macros::panic macros>`.
+fn span_location(span: &RawSpan) -> Location {
+    if span.file_name.starts_with('<')
+        && let Some(expansion) = &span.expansion
+    {
+        return span_location(&expansion.span);
+    }
+
+    range_location(
+        span.file_name.clone(),
+        (span.line_start, Some(span.column_start)),
+        (span.line_end, Some(span.column_end)),
+    )
+}
+
+/// Adds a diagnostic for each primary span of the message, or its children with spans, and the
+/// children without spans as notes.
+fn push_diagnostics(raw: &RawDiagnostic, diagnostics: &mut Vec<Diagnostic>) {
+    let code = raw.code.as_ref().map(|code| code.code.clone());
+    let source = match &code {
+        Some(code) if code.starts_with("clippy::") => "clippy",
+        _ => "rustc",
+    };
+    let notes: Vec<String> = raw
+        .children
+        .iter()
+        .filter(|child| child.spans.is_empty())
+        .map(|child| format!("{}: {}", child.level, child.message))
+        .collect();
+
+    let diagnostic = |span: &RawSpan, raw: &RawDiagnostic, notes: Vec<String>| Diagnostic {
+        link: location_link(&span_location(span)),
+        severity: severity(&raw.level),
+        message: raw.message.clone(),
+        code: code.clone(),
+        source: Some(source.to_string()),
+        label: span.label.clone(),
+        notes,
+    };
+
+    for span in raw.spans.iter().filter(|span| span.is_primary) {
+        diagnostics.push(diagnostic(span, raw, notes.clone()));
+    }
+    for child in &raw.children {
+        for span in child.spans.iter().filter(|span| span.is_primary) {
+            diagnostics.push(diagnostic(span, child, Vec::new()));
+        }
+    }
+}
+
+/// Parses the diagnostics in lines of JSON messages. Lines that aren't compiler messages, such as
+/// artifacts or plain text mixed into the output, are skipped, and so are messages without a
+/// location like `aborting due to 2 previous errors`. Diagnostics reported for several targets of
+/// a package are only returned once.
+///
+/// Paths are relative to the root of the workspace.
+pub fn parse_cargo_messages<S: AsRef<str>>(lines: &[S]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    // The path, row, column, message and code of the diagnostics already returned
+    let mut seen = HashSet::new();
+
+    for line in lines {
+        let line = line.as_ref().trim();
+        if !line.starts_with('{') {
+            continue;
+        }
+
+        let raw = match serde_json::from_str(line) {
+            Ok(RawLine::Cargo {
+                reason,
+                message: Some(raw),
+            }) if reason == "compiler-message" => raw,
+            Ok(RawLine::Rustc(raw)) => raw,
+            _ => continue,
+        };
+
+        let mut new_diagnostics = Vec::new();
+        push_diagnostics(&raw, &mut new_diagnostics);
+        for diagnostic in new_diagnostics {
+            let suffix = diagnostic.link.suffix.as_ref();
+            let key = (
+                diagnostic.link.path.text.clone(),
+                suffix.and_then(|suffix| suffix.row),
+                suffix.and_then(|suffix| suffix.col),
+                diagnostic.message.clone(),
+                diagnostic.code.clone(),
+            );
+            if seen.insert(key) {
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cargo_messages::parse_cargo_messages;
+    use crate::diagnostics::Severity;
+    use pretty_assertions::assert_eq;
+
+    /// The path, source, row, end row and end column of a diagnostic
+    type Location = (
+        String,
+        Option<String>,
+        Option<u32>,
+        Option<u32>,
+        Option<u32>,
+    );
+
+    const MISMATCHED_TYPES: &str = r#"{"reason":"compiler-message","package_id":"path+file:///app#0.1.0","manifest_path":"/app/Cargo.toml","target":{"kind":["bin"],"name":"app","src_path":"/app/src/main.rs"},"message":{"rendered":"error[E0308]: mismatched types\n","$message_type":"diagnostic","children":[{"children":[],"code":null,"level":"note","message":"expected type `u32`","rendered":null,"spans":[]},{"children":[],"code":null,"level":"help","message":"try using a conversion method","rendered":null,"spans":[{"byte_end":120,"byte_start":115,"column_end":23,"column_start":18,"expansion":null,"file_name":"src/main.rs","is_primary":true,"label":null,"line_end":4,"line_start":4,"suggested_replacement":"value.parse().unwrap()","suggestion_applicability":"MaybeIncorrect","text":[]}]}],"code":{"code":"E0308","explanation":"..."},"level":"error","message":"mismatched types","spans":[{"byte_end":120,"byte_start":115,"column_end":23,"column_start":18,"expansion":null,"file_name":"src/main.rs","is_primary":true,"label":"expected `u32`, found `&str`","line_end":4,"line_start":4,"suggested_replacement":null,"suggestion_applicability":null,"text":[]},{"byte_end":100,"byte_start":97,"column_end":15,"column_start":12,"expansion":null,"file_name":"src/main.rs","is_primary":false,"label":"expected due to this","line_end":4,"line_start":4,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}"#;
+
+    #[test]
+    pub fn test_compiler_message() {
+        let lines = [
+            r#"{"reason":"compiler-artifact","package_id":"path+file:///app#0.1.0","target":{"name":"app"},"fresh":true}"#,
+            "   Compiling app v0.1.0 (/app)",
+            MISMATCHED_TYPES,
+            // The same diagnostic for the test target
+            MISMATCHED_TYPES,
+            r#"{"reason":"compiler-message","message":{"$message_type":"diagnostic","children":[],"code":null,"level":"error","message":"aborting due to 1 previous error","spans":[]}}"#,
+            r#"{"reason":"build-finished","success":false}"#,
+        ];
+
+        let diagnostics = parse_cargo_messages(&lines);
+
+        assert_eq!(diagnostics.len(), 2);
+        let error = &diagnostics[0];
+        assert_eq!(
+            (
+                error.severity,
+                error.message.as_str(),
+                error.code.as_deref(),
+                error.source.as_deref(),
+                error.label.as_deref(),
+            ),
+            (
+                Severity::Error,
+                "mismatched types",
+                Some("E0308"),
+                Some("rustc"),
+                Some("expected `u32`, found `&str`")
+            )
+        );
+        assert_eq!(error.notes, vec!["note: expected type `u32`"]);
+        assert_eq!(error.link.path.text, "src/main.rs");
+        let suffix = error.link.suffix.as_ref().unwrap();
+        assert_eq!(
+            (suffix.row, suffix.col, suffix.row_end, suffix.col_end),
+            (Some(4), Some(18), None, Some(22))
+        );
+        assert_eq!(
+            (suffix.suffix.index, suffix.suffix.text.as_str()),
+            (11, ":4:18-22")
+        );
+
+        let help = &diagnostics[1];
+        assert_eq!(
+            (help.severity, help.message.as_str(), help.label.as_deref()),
+            (Severity::Hint, "try using a conversion method", None)
+        );
+    }
+
+    #[test]
+    pub fn test_multiline_span_and_macro_expansion() {
+        let lines = [
+            r#"{"$message_type":"diagnostic","children":[],"code":{"code":"clippy::needless_return","explanation":null},"level":"warning","message":"unneeded `return` statement","spans":[{"column_end":2,"column_start":5,"expansion":null,"file_name":"src/lib.rs","is_primary":true,"label":null,"line_end":12,"line_start":10}]}"#,
+            r#"{"$message_type":"diagnostic","children":[],"code":null,"level":"error","message":"format argument must be a string literal","spans":[{"column_end":30,"column_start":1,"expansion":{"def_site_span":null,"macro_decl_name":"println!","span":{"column_end":20,"column_start":5,"expansion":null,"file_name":"src/main.rs","is_primary":false,"label":null,"line_end":2,"line_start":2}},"file_name":"<::std::macros::println macros>","is_primary":true,"label":null,"line_end":1,"line_start":1}]}"#,
+        ];
+
+        let locations: Vec<Location> = parse_cargo_messages(&lines)
+            .into_iter()
+            .map(|diagnostic| {
+                let suffix = diagnostic.link.suffix.unwrap();
+                (
+                    diagnostic.link.path.text,
+                    diagnostic.source,
+                    suffix.row,
+                    suffix.row_end,
+                    suffix.col_end,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            locations,
+            vec![
+                (
+                    "src/lib.rs".to_string(),
+                    Some("clippy".to_string()),
+                    Some(10),
+                    Some(12),
+                    Some(1)
+                ),
+                (
+                    "src/main.rs".to_string(),
+                    Some("rustc".to_string()),
+                    Some(2),
+                    None,
+                    Some(19)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Scoring of how likely a detected link is to point to a file, from 0 to 100. Bare words such as
+//! `and/or` or `1/2` are detected as paths just like `src/main.rs:10:5`, the score allows callers to
+//! drop the unlikely ones.
+
+use crate::ParsedLink;
+
+/// The score every link starts with.
+const BASE_SCORE: i32 = 10;
+
+/// Extensions of common source, configuration and documentation files.
+const KNOWN_EXTENSIONS: &[&str] = &[
+    "c", "cc", "cfg", "cjs", "conf", "cpp", "cs", "css", "csv", "dart", "ex", "exs", "go", "h",
+    "hpp", "hs", "html", "ini", "java", "js", "json", "jsx", "kt", "lock", "log", "lua", "md",
+    "mjs", "nix", "php", "pl", "py", "rb", "rs", "rst", "scala", "scss", "sh", "sql", "swift",
+    "toml", "ts", "tsx", "txt", "vim", "vue", "xml", "yaml", "yml", "zig",
+];
+
+/// Words that tools print right before a location, eg. `--> src/main.rs:10:5` or
+/// `at foo (src/index.js:1:2)`.
+const CONTEXT_WORDS: &[&str] = &["-->", "at", "File", "file", "from", "in", "see", "See"];
+
+/// Scores the link found in `line`, see [`ParsedLink::confidence`].
+pub(crate) fn score_link(line: &str, link: &ParsedLink) -> u8 {
+    let path = link.path.text.as_str();
+    let mut score = BASE_SCORE;
+
+    if link
+        .suffix
+        .as_ref()
+        .is_some_and(|suffix| suffix.row.is_some())
+    {
+        score += 40;
+    }
+
+    match extension(path) {
+        Some(extension) if KNOWN_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()) => {
+            score += 25
+        }
+        Some(_) => score += 10,
+        None => {}
+    }
+
+    if has_path_prefix(path) {
+        score += 20;
+    }
+
+    // Quotes and markup explicitly delimit the path
+    if link.prefix.is_some() {
+        score += 15;
+    }
+
+    if path.contains(['/', '\\']) {
+        score += 10;
+    }
+
+    if follows_context_word(line, link) {
+        score += 10;
+    }
+
+    // Fractions and dates such as `1/2` or `10/20/2020`
+    if !path.chars().any(char::is_alphabetic) {
+        score -= 30;
+    }
+
+    score.clamp(0, 100) as u8
+}
+
+/// Returns the extension of the file name, ie. the text after the last `.` when it contains a
+/// letter and isn't too long to be an extension.
+fn extension(path: &str) -> Option<&str> {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let (stem, extension) = name.rsplit_once('.')?;
+    (!stem.is_empty()
+        && (1..=8).contains(&extension.len())
+        && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        && extension.chars().any(|c| c.is_ascii_alphabetic()))
+    .then_some(extension)
+}
+
+/// Whether the path starts like a path, eg. `./`, `../`, `/`, `~`, `C:\` or `\\server`.
+fn has_path_prefix(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let has_drive_letter = bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+
+    ["./", "../", ".\\", "..\\", "/", "\\", "~"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+        || has_drive_letter
+}
+
+/// Whether the link is preceded by one of the [`CONTEXT_WORDS`].
+fn follows_context_word(line: &str, link: &ParsedLink) -> bool {
+    let start = link.prefix.as_ref().map_or(link.path.index, |p| p.index);
+    let before = line[..start].trim_end_matches([' ', '\t', '(', '"', '\'']);
+
+    CONTEXT_WORDS.iter().any(|word| {
+        before.strip_suffix(word).is_some_and(|rest| {
+            rest.chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric())
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::filters::LinkFilters;
+    use crate::{OperatingSystem, detect_links_with_filters, detect_links_with_min_confidence};
+    use pretty_assertions::assert_eq;
+
+    fn confidences(line: &str) -> Vec<(String, u8)> {
+        // Fractions such as `1/2` are rejected by the default filters
+        detect_links_with_filters(line, OperatingSystem::Linux, &LinkFilters::none())
+            .into_iter()
+            .map(|link| (link.path.text, link.confidence))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_confidence() {
+        let cases = [
+            ("error --> src/main.rs:10:5", "src/main.rs", 95),
+            ("\"foo\", line 5", "foo", 65),
+            ("read ./README.md", "./README.md", 65),
+            ("use src/lib", "src/lib", 20),
+            ("and/or", "and/or", 20),
+            ("1/2", "1/2", 0),
+        ];
+
+        for (line, path, confidence) in cases {
+            assert_eq!(
+                confidences(line),
+                vec![(path.to_string(), confidence)],
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_confidence_is_clamped() {
+        let results = confidences("at \"./src/main.rs\", line 10");
+
+        assert_eq!(results, vec![("./src/main.rs".to_string(), 100)]);
+    }
+
+    #[test]
+    pub fn test_min_confidence() {
+        let results = detect_links_with_min_confidence(
+            "1/2 and/or see src/main.rs:10",
+            OperatingSystem::Linux,
+            50,
+        );
+
+        let paths: Vec<&str> = results.iter().map(|l| l.path.text.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs"]);
+    }
+}
@@ -0,0 +1,336 @@
+//! Diagnostics read from the structured output of compilers and linters, whose locations are exact
+//! ranges rather than links detected in rendered text.
+
+use crate::format::{Location, LocationFormat, format_location};
+use crate::permalink::percent_decode;
+use crate::{LinkPartialRange, LinkSuffix, ParsedLink};
+use crate::{cargo_messages, lsp_diagnostics, sarif};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, lua};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticFormat {
+    /// The JSON lines of `cargo build --message-format=json`
+    Cargo,
+    /// A SARIF log
+    Sarif,
+    /// LSP `textDocument/publishDiagnostics` notifications or their params
+    Lsp,
+}
+
+impl FromStr for DiagnosticFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cargo" => Ok(Self::Cargo),
+            "sarif" => Ok(Self::Sarif),
+            "lsp" => Ok(Self::Lsp),
+            _ => Err(format!("unknown diagnostic format: {}", s)),
+        }
+    }
+}
+
+/// The severity of a diagnostic, named like the severities of `vim.diagnostic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// The location of the diagnostic, see [`location_link`]
+    pub link: ParsedLink,
+    pub severity: Severity,
+    pub message: String,
+    /// The code of the diagnostic, eg. `E0308` or `clippy::needless_return`
+    pub code: Option<String>,
+    /// The tool reporting the diagnostic, eg. `rustc` or `clippy`
+    pub source: Option<String>,
+    /// The label of the location, eg. `expected u32, found &str`
+    pub label: Option<String>,
+    /// Notes and help without a location of their own
+    pub notes: Vec<String>,
+}
+
+impl ToObject for Diagnostic {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for Diagnostic {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+impl DiagnosticFormat {
+    /// Detects the format of the content, SARIF logs are a single object with `runs`, cargo
+    /// messages are lines of objects with a `reason` and LSP notifications, or their params, have
+    /// `diagnostics`. Fails when the content is none of them.
+    pub fn detect(text: &str) -> Result<Self, String> {
+        let is_cargo =
+            |value: &Value| value.get("reason").is_some() || value.get("$message_type").is_some();
+        let is_lsp = |value: &Value| {
+            value
+                .get("params")
+                .unwrap_or(value)
+                .get("diagnostics")
+                .is_some()
+        };
+
+        let first = match serde_json::from_str::<Value>(text) {
+            Ok(value) if value.get("runs").is_some() => return Ok(Self::Sarif),
+            Ok(Value::Array(values)) => values.into_iter().next(),
+            Ok(value) => Some(value),
+            Err(_) => text
+                .lines()
+                .find_map(|line| serde_json::from_str::<Value>(line).ok()),
+        };
+        match first {
+            Some(value) if is_cargo(&value) => Ok(Self::Cargo),
+            Some(value) if is_lsp(&value) => Ok(Self::Lsp),
+            _ => Err("unknown diagnostic format".to_string()),
+        }
+    }
+}
+
+/// Parses the diagnostics in the content of a file of the format.
+pub fn parse_diagnostics(text: &str, format: DiagnosticFormat) -> Result<Vec<Diagnostic>, String> {
+    match format {
+        DiagnosticFormat::Cargo => Ok(cargo_messages::parse_cargo_messages(
+            &text.lines().collect::<Vec<_>>(),
+        )),
+        DiagnosticFormat::Sarif => sarif::parse_sarif(text),
+        DiagnosticFormat::Lsp => lsp_diagnostics::parse_publish_diagnostics(text),
+    }
+}
+
+/// Reads the diagnostics of a file, detecting its format when it isn't given.
+pub fn read_diagnostics_file(
+    path: &Path,
+    format: Option<DiagnosticFormat>,
+) -> Result<Vec<Diagnostic>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let error = |e: String| format!("{}: {}", path.display(), e);
+    let format = match format {
+        Some(format) => format,
+        None => DiagnosticFormat::detect(&text).map_err(error)?,
+    };
+    parse_diagnostics(&text, format).map_err(error)
+}
+
+/// Returns the link for a location, with the exact rows and columns of the location in its suffix.
+/// Diagnostics aren't printed, so the ranges of the link are synthetic and point into the location
+/// formatted with [`LocationFormat::Colon`], or with [`LocationFormat::Line`] for a range of rows
+/// without columns, which the colon format can't express.
+pub fn location_link(location: &Location) -> ParsedLink {
+    let format = if location.col.is_none() && location.row_end.is_some() {
+        LocationFormat::Line
+    } else {
+        LocationFormat::Colon
+    };
+    let text = format_location(location, format);
+    // Skip the quotes around the path
+    let path_index = usize::from(!text.starts_with(&location.path));
+    let suffix_index = 2 * path_index + location.path.len();
+
+    ParsedLink {
+        path: LinkPartialRange {
+            index: path_index,
+            text: location.path.clone(),
+        },
+        prefix: None,
+        suffix: Some(LinkSuffix {
+            row: Some(location.row),
+            col: location.col,
+            row_end: location.row_end,
+            col_end: location.col_end,
+            suffix: LinkPartialRange {
+                index: suffix_index,
+                text: text[suffix_index..].to_string(),
+            },
+        }),
+        confidence: 100,
+    }
+}
+
+/// Returns the location of a range of 1-based rows and columns whose end column is exclusive, like
+/// the spans of compilers and linters. Empty ranges only keep their start.
+pub(crate) fn range_location(
+    path: String,
+    (row, col): (u32, Option<u32>),
+    (row_end, col_end): (u32, Option<u32>),
+) -> Location {
+    let multiline = row_end > row;
+    let col_end = col_end
+        .filter(|col_end| *col_end > 1)
+        .map(|col_end| col_end - 1)
+        .filter(|col_end| multiline || col.is_some_and(|col| *col_end >= col));
+
+    Location {
+        path,
+        row,
+        col,
+        row_end: multiline.then_some(row_end),
+        col_end,
+    }
+}
+
+/// Returns the path of a `file://` URI, or the percent decoded URI itself for relative references
+/// such as `src/main.rs`.
+pub(crate) fn uri_path(uri: &str) -> String {
+    let Some(rest) = uri.strip_prefix("file://") else {
+        return percent_decode(uri);
+    };
+
+    // Drop the host of `file://host/path`
+    let path = percent_decode(&rest[rest.find('/').unwrap_or(rest.len())..]);
+    // `file:///C:/foo` is the path `C:/foo`
+    match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::diagnostics::{DiagnosticFormat, location_link, read_diagnostics_file};
+    use crate::format::Location;
+    use crate::test_util::TempDir;
+    use crate::{OperatingSystem, detect_links};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    pub fn test_detect_format() {
+        let cases = [
+            (
+                r#"{"version": "2.1.0", "runs": []}"#,
+                DiagnosticFormat::Sarif,
+            ),
+            (
+                r#"{"uri": "file:///a.rs", "diagnostics": []}"#,
+                DiagnosticFormat::Lsp,
+            ),
+            (
+                "[{\"uri\": \"file:///a.rs\", \"diagnostics\": []}]",
+                DiagnosticFormat::Lsp,
+            ),
+            (
+                "{\"reason\":\"compiler-artifact\"}\n{\"reason\":\"build-finished\"}",
+                DiagnosticFormat::Cargo,
+            ),
+            (
+                "   Compiling app\n{\"$message_type\":\"diagnostic\"}\n{}",
+                DiagnosticFormat::Cargo,
+            ),
+            (
+                "{\"jsonrpc\": \"2.0\", \"params\": {\"uri\": \"file:///a.rs\", \"diagnostics\": []}}\n{\"uri\": \"file:///b.rs\", \"diagnostics\": []}",
+                DiagnosticFormat::Lsp,
+            ),
+        ];
+
+        for (text, format) in cases {
+            assert_eq!(DiagnosticFormat::detect(text), Ok(format), "{}", text);
+        }
+
+        for text in [
+            "",
+            "error: could not compile `app`",
+            r#"{"version": 1}"#,
+            "[]",
+        ] {
+            assert_eq!(
+                DiagnosticFormat::detect(text),
+                Err("unknown diagnostic format".to_string()),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_read_diagnostics_file() {
+        let dir = TempDir::new("diagnostics");
+        let file = dir.join("results.sarif");
+        std::fs::write(
+            &file,
+            r#"{"runs": [{"tool": {"driver": {"name": "lint"}}, "results": [{"message": {"text": "bad"}, "locations": [{"physicalLocation": {"artifactLocation": {"uri": "src/a.py"}, "region": {"startLine": 2}}}]}]}]}"#,
+        )
+        .unwrap();
+
+        let diagnostics = read_diagnostics_file(&file, None).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].link.path.text, "src/a.py");
+        assert!(read_diagnostics_file(&file, Some(DiagnosticFormat::Lsp)).is_err());
+        assert!(read_diagnostics_file(&dir.join("missing.sarif"), None).is_err());
+
+        let text = dir.join("build.log");
+        std::fs::write(&text, "error[E0308]: mismatched types\n").unwrap();
+        assert_eq!(
+            read_diagnostics_file(&text, None),
+            Err(format!("{}: unknown diagnostic format", text.display()))
+        );
+    }
+
+    #[test]
+    pub fn test_location_link() {
+        let location = |path: &str, col, row_end, col_end| Location {
+            path: path.to_string(),
+            row: 3,
+            col,
+            row_end,
+            col_end,
+        };
+        let cases = [
+            (
+                location("src/a.rs", Some(5), None, Some(9)),
+                "src/a.rs:3:5-9",
+            ),
+            (location("my a.rs", Some(5), None, None), "\"my a.rs\":3:5"),
+            (
+                location("src/a.rs", None, Some(7), None),
+                "\"src/a.rs\", lines 3-7",
+            ),
+            (location("src/a.rs", None, None, None), "src/a.rs:3"),
+        ];
+
+        for (location, text) in cases {
+            let link = location_link(&location);
+            let suffix = link.suffix.as_ref().unwrap();
+            assert_eq!(
+                (suffix.row, suffix.col, suffix.row_end, suffix.col_end),
+                (Some(3), location.col, location.row_end, location.col_end)
+            );
+            assert_eq!(
+                &text[link.path.index..][..location.path.len()],
+                location.path
+            );
+            assert_eq!(&text[suffix.suffix.index..], suffix.suffix.text);
+            assert_eq!(
+                detect_links(text, OperatingSystem::Linux)[0]
+                    .suffix
+                    .as_ref()
+                    .unwrap()
+                    .row_end,
+                location.row_end,
+                "{}",
+                text
+            );
+        }
+    }
+}
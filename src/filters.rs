@@ -0,0 +1,194 @@
+//! Rules that reject links which are more likely to be other kinds of numbers, such as the date
+//! `2024/10/17` being detected as a path or the `:30` of the time `12:30` as a suffix.
+//!
+//! The line is split into words and each word that is entirely a date, time, IP address, version or
+//! fraction rejects the links whose path it overlaps. It also rejects links whose suffix is only
+//! the start of the word, such as the ` 80` of `TCP 80/443` which would otherwise be line 80 of
+//! `TCP`. Words are delimited by whitespace so numbers within paths, such as `src/v1.2/main.rs`,
+//! aren't affected.
+
+use crate::{ParsedLink, link_range};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// The false positive rules to apply in [`crate::detect_links_with_filters`], all of which are
+/// enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkFilters {
+    /// Dates such as `2024/10/17`, `2024-10-17` and `10/17/2024`
+    pub dates: bool,
+    /// Times such as `12:30`, `12:30:45` and `1:23.45`
+    pub times: bool,
+    /// IP addresses and ports such as `192.168.1.1:8080`, `[::1]:8080` and `localhost:3000`
+    pub ip_ports: bool,
+    /// Versions such as `1.2.3`, `v1.2` and `v1.2/3`
+    pub versions: bool,
+    /// Fractions and ratios such as `1/2` and `80/443`
+    pub fractions: bool,
+}
+
+impl Default for LinkFilters {
+    fn default() -> Self {
+        Self {
+            dates: true,
+            times: true,
+            ip_ports: true,
+            versions: true,
+            fractions: true,
+        }
+    }
+}
+
+impl LinkFilters {
+    /// Disables all rules.
+    pub fn none() -> Self {
+        Self {
+            dates: false,
+            times: false,
+            ip_ports: false,
+            versions: false,
+            fractions: false,
+        }
+    }
+
+    fn rules(&self) -> impl Iterator<Item = &'static Regex> {
+        [
+            (self.dates, &*DATE_REGEX),
+            (self.times, &*TIME_REGEX),
+            (self.ip_ports, &*IP_PORT_REGEX),
+            (self.versions, &*VERSION_REGEX),
+            (self.fractions, &*FRACTION_REGEX),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, regex)| enabled.then_some(regex))
+    }
+
+    /// Removes the links that overlap a word matched by one of the enabled rules.
+    pub(crate) fn apply(&self, line: &str, links: &mut Vec<ParsedLink>) {
+        if *self == Self::none() || links.is_empty() {
+            return;
+        }
+
+        let words: Vec<(usize, usize)> = words(line)
+            .filter(|(start, end)| {
+                self.rules()
+                    .any(|regex| regex.is_match(&line[*start..*end]))
+            })
+            .collect();
+        if words.is_empty() {
+            return;
+        }
+
+        links.retain(|link| {
+            let path_start = link.path.index;
+            let path_end = path_start + link.path.text.len();
+            let (_, link_end) = link_range(link);
+
+            !words.iter().any(|(start, end)| {
+                let overlaps_path = *start < path_end && *end > path_start;
+                let extends_suffix = link.suffix.is_some() && *start < link_end && *end > link_end;
+                overlaps_path || extends_suffix
+            })
+        });
+    }
+}
+
+static DATE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d{4}[/.-]\d{1,2}[/.-]\d{1,2}|\d{1,2}[/.-]\d{1,2}[/.-]\d{2,4})$").unwrap()
+});
+
+static TIME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\d{1,2}:\d{2}(?::\d{2})?(?:[.,]\d+)?$").unwrap());
+
+static IP_PORT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d{1,3}(?:\.\d{1,3}){3}|\[[0-9a-fA-F:.]+\]|localhost)(?::\d{1,5})?$").unwrap()
+});
+
+static VERSION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:v\d+(?:\.\d+)*|\d+(?:\.\d+)+)(?:/[\d.]+)?$").unwrap());
+
+static FRACTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+(?:/\d+)+$").unwrap());
+
+/// Returns the ranges of the whitespace delimited words in the line, without any punctuation
+/// surrounding them such as the parentheses of `(12:30)` or the comma of `10/17/2024,`.
+fn words(line: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let is_punctuation = |c: char| matches!(c, '(' | ')' | '"' | '\'' | ',' | ';' | '=');
+
+    line.split_whitespace().filter_map(move |word| {
+        let start = word.as_ptr() as usize - line.as_ptr() as usize;
+        let trimmed_start = word.trim_start_matches(is_punctuation);
+        let trimmed = trimmed_start.trim_end_matches(is_punctuation);
+        let trimmed = trimmed.strip_suffix(['.', ':']).unwrap_or(trimmed);
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let start = start + (word.len() - trimmed_start.len());
+        Some((start, start + trimmed.len()))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::filters::LinkFilters;
+    use crate::{OperatingSystem, detect_links, detect_links_with_filters};
+    use pretty_assertions::assert_eq;
+
+    fn paths(line: &str) -> Vec<String> {
+        detect_links(line, OperatingSystem::Linux)
+            .into_iter()
+            .map(|link| link.path.text)
+            .collect()
+    }
+
+    #[test]
+    pub fn test_false_positives_are_rejected() {
+        let lines = [
+            "2024/10/17 12:30:45",
+            "v1.2/3",
+            "TCP 80/443",
+            "12:30",
+            "at 12:30:45 on 10/17/2024, 1/2 done",
+            "listening on 192.168.1.1:8080",
+            "127.0.0.1:80 [::1]:8080 localhost:3000",
+            "released 2024-10-17 as version 1.2.3/4",
+            "took 1:23.45",
+        ];
+
+        for line in lines {
+            assert_eq!(paths(line), Vec::<String>::new(), "{}", line);
+        }
+    }
+
+    #[test]
+    pub fn test_real_paths_survive() {
+        let cases = [
+            ("src/v1.2/main.rs:3", vec!["src/v1.2/main.rs"]),
+            ("logs/2024/10/17.log:12:30", vec!["logs/2024/10/17.log"]),
+            ("2024-10-17.log:3 at noon", vec!["2024-10-17.log"]),
+            ("/srv/1/2 and ./v1.2", vec!["/srv/1/2", "./v1.2"]),
+            ("foo:12:30 and bar 80", vec!["foo", "bar"]),
+        ];
+
+        for (line, expected) in cases {
+            assert_eq!(paths(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    pub fn test_filters_can_be_disabled() {
+        let filters = LinkFilters {
+            fractions: false,
+            ..LinkFilters::default()
+        };
+        let results =
+            detect_links_with_filters("1/2 done at 12:30:45", OperatingSystem::Linux, &filters);
+
+        let paths: Vec<&str> = results.iter().map(|l| l.path.text.as_str()).collect();
+        assert_eq!(paths, vec!["1/2"]);
+
+        let results =
+            detect_links_with_filters("12:30", OperatingSystem::Linux, &LinkFilters::none());
+        assert_eq!(results[0].path.text, "12");
+    }
+}
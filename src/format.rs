@@ -0,0 +1,429 @@
+//! Formatting of locations into strings, which is the inverse of parsing link suffixes, ie. the
+//! output of [`format_location`] can be parsed again with [`crate::get_link_suffix`].
+
+use crate::permalink::{self, Repository};
+use nvim_oxi::api;
+use nvim_oxi::{Array, Object};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocationFormat {
+    /// `foo:339`, `foo:339:12`, `foo:339:12-789` and `foo:339:12-341.789`
+    Colon,
+    /// `foo(339)` and `foo(339,12)`
+    Parentheses,
+    /// `"foo", line 339`, `"foo", line 339, col 12` and `"foo", lines 339-341, characters 12-789`
+    Line,
+}
+
+impl FromStr for LocationFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "colon" => Ok(Self::Colon),
+            "parentheses" => Ok(Self::Parentheses),
+            "line" => Ok(Self::Line),
+            _ => Err(format!("unknown location format: {}", s)),
+        }
+    }
+}
+
+/// The formats of [`format_current_location`], which can also link to the file on its forge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurrentLocationFormat {
+    Location(LocationFormat),
+    /// A permalink to the file on the forge hosting the repository, see
+    /// [`permalink::format_permalink`]
+    Permalink,
+}
+
+impl FromStr for CurrentLocationFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "permalink" => Ok(Self::Permalink),
+            _ => s.parse().map(Self::Location),
+        }
+    }
+}
+
+/// A location in a file, rows and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub path: String,
+    pub row: u32,
+    pub col: Option<u32>,
+    pub row_end: Option<u32>,
+    pub col_end: Option<u32>,
+}
+
+/// Formats the location as a path with a link suffix. Parts of the location that can't be
+/// represented by the format are dropped, ie. ranges without columns are only supported by
+/// [`LocationFormat::Line`] and [`LocationFormat::Parentheses`] only supports a row and column.
+pub fn format_location(location: &Location, format: LocationFormat) -> String {
+    match format {
+        LocationFormat::Colon => {
+            let mut s = format!("{}:{}", quote_path(&location.path), location.row);
+            if let Some(col) = location.col {
+                s += &format!(":{}", col);
+                match (location.row_end, location.col_end) {
+                    (Some(row_end), Some(col_end)) => s += &format!("-{}.{}", row_end, col_end),
+                    (None, Some(col_end)) => s += &format!("-{}", col_end),
+                    _ => {}
+                }
+            }
+            s
+        }
+        LocationFormat::Parentheses => match location.col {
+            Some(col) => format!("{}({},{})", quote_path(&location.path), location.row, col),
+            None => format!("{}({})", quote_path(&location.path), location.row),
+        },
+        LocationFormat::Line => {
+            let quote = if location.path.contains('"') {
+                '\''
+            } else {
+                '"'
+            };
+            let mut s = match location.row_end {
+                Some(row_end) => format!(
+                    "{0}{1}{0}, lines {2}-{3}",
+                    quote, location.path, location.row, row_end
+                ),
+                None => format!("{0}{1}{0}, line {2}", quote, location.path, location.row),
+            };
+            match (location.col, location.col_end) {
+                (Some(col), Some(col_end)) => s += &format!(", characters {}-{}", col, col_end),
+                (Some(col), None) if location.row_end.is_some() => {
+                    s += &format!(", character {}", col)
+                }
+                (Some(col), None) => s += &format!(", col {}", col),
+                _ => {}
+            }
+            s
+        }
+    }
+}
+
+/// Templates for every format supported by the link suffix regex, mirroring the clauses in
+/// `generate_link_suffix_regex`. The placeholders `{path}`, `{row}`, `{col}`, `{row_end}` and
+/// `{col_end}` are filled in by [`format_with_template`].
+pub static SUFFIX_TEMPLATES: Lazy<Vec<String>> = Lazy::new(|| {
+    let mut templates = Vec::new();
+
+    // foo:339, foo#339:12, foo 339.12-789, foo, 339:12-341.789, "foo",339 ...
+    for path in [
+        "{path}:",
+        "{path}#",
+        "{path} ",
+        "{path}, ",
+        "\"{path}\",",
+        "'{path}',",
+    ] {
+        for col in ["", ":{col}", ".{col}"] {
+            for col_end in ["", "-{col_end}", "-{row_end}.{col_end}"] {
+                if col.is_empty() && !col_end.is_empty() {
+                    continue;
+                }
+                templates.push(format!("{}{{row}}{}{}", path, col, col_end));
+            }
+        }
+    }
+
+    // "foo", line 339, foo:line 339, col 12, 'foo' on lines 339-341, characters 12-789 ...
+    for path in ["{path}", "\"{path}\"", "'{path}'"] {
+        for separator in [", ", " ", ":", ": ", " on "] {
+            for row in ["line {row}", "lines {row}-{row_end}"] {
+                templates.push(format!("{}{}{}", path, separator, row));
+                for col_separator in [", ", " "] {
+                    for col in ["col", "column", "character", "characters"] {
+                        for col_end in ["", "-{col_end}"] {
+                            templates.push(format!(
+                                "{}{}{}{}{} {{col}}{}",
+                                path, separator, row, col_separator, col, col_end
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // foo(339), foo[339,12], foo (339, 12), foo: (339:12) ...
+    for separator in ["", " ", ":", ": "] {
+        for (open, close) in [('(', ')'), ('[', ']')] {
+            for col in ["", ",{col}", ", {col}", ":{col}"] {
+                templates.push(format!(
+                    "{{path}}{}{}{{row}}{}{}",
+                    separator, open, col, close
+                ));
+            }
+        }
+    }
+
+    templates
+});
+
+/// Formats the location with one of the [`SUFFIX_TEMPLATES`]. Returns `None` if the template and
+/// the location don't have the same set of rows and columns. The path is inserted as is, so paths
+/// containing whitespace must use a quoted template.
+pub fn format_with_template(template: &str, location: &Location) -> Option<String> {
+    let fields = [
+        ("{col}", location.col),
+        ("{row_end}", location.row_end),
+        ("{col_end}", location.col_end),
+    ];
+    if fields
+        .iter()
+        .any(|(placeholder, value)| template.contains(placeholder) != value.is_some())
+    {
+        return None;
+    }
+
+    let mut formatted = template
+        .replace("{path}", &location.path)
+        .replace("{row}", &location.row.to_string());
+    for (placeholder, value) in fields {
+        if let Some(value) = value {
+            formatted = formatted.replace(placeholder, &value.to_string());
+        }
+    }
+    Some(formatted)
+}
+
+/// Paths containing whitespace need to be quoted for the suffix to be parsed with the whole path.
+pub(crate) fn quote_path(path: &str) -> String {
+    if path.contains(char::is_whitespace) {
+        format!("\"{}\"", path)
+    } else {
+        path.to_string()
+    }
+}
+
+/// Formats the location of the cursor, or the visual selection, in the current buffer. Returns
+/// `None` if the buffer has no file or, for permalinks, the file isn't in a git repository with a
+/// known remote.
+pub(crate) fn format_current_location(
+    format: CurrentLocationFormat,
+) -> nvim_oxi::Result<Option<String>> {
+    let name = api::get_current_buf().get_name()?;
+    if name.as_os_str().is_empty() {
+        return Ok(None);
+    }
+
+    let (row, col) = api::get_current_win().get_cursor()?;
+    let mut location = Location {
+        path: String::new(),
+        row: row as u32,
+        col: Some(col as u32 + 1),
+        row_end: None,
+        col_end: None,
+    };
+
+    let mode: String = api::call_function("mode", Array::new())?;
+    if let Some(kind) = mode
+        .chars()
+        .next()
+        .filter(|c| matches!(c, 'v' | 'V' | '\x16'))
+    {
+        // getpos() returns [bufnum, lnum, col, off]
+        let start: Vec<i64> = api::call_function("getpos", Array::from_iter([Object::from("v")]))?;
+        let start = (start[1] as u32, start[2] as u32);
+        let end = (location.row, location.col.unwrap_or(1));
+        let ((row, col), (row_end, col_end)) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+
+        location.row = row;
+        location.row_end = (row_end != row).then_some(row_end);
+        // Linewise selections cover whole lines so they don't have columns
+        (location.col, location.col_end) = if kind == 'V' {
+            (None, None)
+        } else {
+            (Some(col), Some(col_end))
+        };
+    }
+
+    let format = match format {
+        CurrentLocationFormat::Location(format) => format,
+        CurrentLocationFormat::Permalink => return Ok(format_permalink_for_file(&name, location)),
+    };
+
+    location.path = api::call_function(
+        "fnamemodify",
+        Array::from_iter([
+            Object::from(name.to_string_lossy().as_ref()),
+            Object::from(":."),
+        ]),
+    )?;
+    Ok(Some(format_location(&location, format)))
+}
+
+/// Formats a permalink to the file at its current commit, using the repository's `origin` remote
+/// or otherwise the first remote.
+fn format_permalink_for_file(file: &Path, mut location: Location) -> Option<String> {
+    let dir = file.parent()?;
+    let repo_root = permalink::git(dir, &["rev-parse", "--show-toplevel"])?;
+    let git_ref = permalink::git(dir, &["rev-parse", "HEAD"])?;
+    let remote = permalink::git(dir, &["remote", "get-url", "origin"]).or_else(|| {
+        let remote = permalink::git(dir, &["remote"])?;
+        permalink::git(dir, &["remote", "get-url", remote.lines().next()?])
+    })?;
+
+    let repository = Repository::from_remote_url(remote.trim())?;
+    let relative = file.strip_prefix(repo_root.trim()).ok()?;
+    location.path = relative.to_string_lossy().replace('\\', "/");
+
+    Some(permalink::format_permalink(
+        &repository,
+        git_ref.trim(),
+        &location,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::format::{CurrentLocationFormat, Location, LocationFormat, format_location};
+    use crate::{LinkSuffix, get_link_suffix, remove_link_suffix};
+    use pretty_assertions::assert_eq;
+
+    fn location(
+        path: &str,
+        col: Option<u32>,
+        row_end: Option<u32>,
+        col_end: Option<u32>,
+    ) -> Location {
+        Location {
+            path: path.to_string(),
+            row: 339,
+            col,
+            row_end,
+            col_end,
+        }
+    }
+
+    #[test]
+    pub fn test_format_location() {
+        let cases = [
+            (
+                location("foo", None, None, None),
+                LocationFormat::Colon,
+                "foo:339",
+            ),
+            (
+                location("foo", Some(12), None, None),
+                LocationFormat::Colon,
+                "foo:339:12",
+            ),
+            (
+                location("foo", Some(12), None, Some(789)),
+                LocationFormat::Colon,
+                "foo:339:12-789",
+            ),
+            (
+                location("foo", Some(12), Some(341), Some(789)),
+                LocationFormat::Colon,
+                "foo:339:12-341.789",
+            ),
+            (
+                location("foo", Some(12), None, None),
+                LocationFormat::Parentheses,
+                "foo(339,12)",
+            ),
+            (
+                location("foo", None, None, None),
+                LocationFormat::Line,
+                "\"foo\", line 339",
+            ),
+            (
+                location("foo", Some(12), None, None),
+                LocationFormat::Line,
+                "\"foo\", line 339, col 12",
+            ),
+            (
+                location("foo", None, Some(341), None),
+                LocationFormat::Line,
+                "\"foo\", lines 339-341",
+            ),
+            (
+                location("foo", Some(12), Some(341), Some(789)),
+                LocationFormat::Line,
+                "\"foo\", lines 339-341, characters 12-789",
+            ),
+            (
+                location("my foo", Some(12), None, None),
+                LocationFormat::Colon,
+                "\"my foo\":339:12",
+            ),
+        ];
+
+        for (location, format, expected) in cases {
+            assert_eq!(format_location(&location, format), expected);
+        }
+    }
+
+    #[test]
+    pub fn test_format_location_round_trip() {
+        let locations = [
+            location("foo", None, None, None),
+            location("foo", Some(12), None, None),
+            location("foo", Some(12), None, Some(789)),
+            location("foo", Some(12), Some(341), Some(789)),
+            location("foo", None, Some(341), None),
+            location("foo", Some(12), Some(341), None),
+        ];
+
+        for location in &locations {
+            for format in [
+                LocationFormat::Colon,
+                LocationFormat::Parentheses,
+                LocationFormat::Line,
+            ] {
+                let formatted = format_location(location, format);
+                let suffix: LinkSuffix = get_link_suffix(&formatted).expect(&formatted);
+
+                // Only compare what the format is able to represent
+                let expected = match format {
+                    LocationFormat::Parentheses => (Some(339), location.col, None, None),
+                    LocationFormat::Colon if location.col.is_none() => {
+                        (Some(339), None, None, None)
+                    }
+                    LocationFormat::Colon => (
+                        Some(339),
+                        location.col,
+                        location.row_end.filter(|_| location.col_end.is_some()),
+                        location.col_end,
+                    ),
+                    _ => (Some(339), location.col, location.row_end, location.col_end),
+                };
+                assert_eq!(
+                    (suffix.row, suffix.col, suffix.row_end, suffix.col_end),
+                    expected,
+                    "{}",
+                    formatted
+                );
+                assert!(
+                    remove_link_suffix(&formatted).ends_with("foo"),
+                    "{}",
+                    formatted
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_parse_current_location_format() {
+        assert_eq!(
+            "line".parse(),
+            Ok(CurrentLocationFormat::Location(LocationFormat::Line))
+        );
+        assert_eq!("permalink".parse(), Ok(CurrentLocationFormat::Permalink));
+        assert!("permalink".parse::<LocationFormat>().is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Hint mode, like tmux-fingers or kitty hints: every link in the visible part of the window is
+//! labeled with a few keys that select it.
+
+use crate::{OperatingSystem, ParsedLink, detect_links_with_min_confidence};
+use nvim_oxi::api::opts::SetExtmarkOpts;
+use nvim_oxi::api::types::ExtmarkVirtTextPosition;
+use nvim_oxi::api::{self, Buffer};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Array, Object, lua};
+use serde::Serialize;
+
+/// Home row keys first, then the rest of the letters
+pub const DEFAULT_ALPHABET: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hint {
+    pub label: String,
+    /// The 1-based row of the line containing the link
+    pub row: usize,
+    pub link: ParsedLink,
+}
+
+impl ToObject for Hint {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for Hint {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// Returns `count` labels made of the characters of `alphabet`, which must have at least two
+/// characters. All labels have the same length so none of them is the start of another, and the
+/// same count always gives the same labels.
+pub fn hint_labels(count: usize, alphabet: &[char]) -> Vec<String> {
+    let mut length = 1;
+    while alphabet.len().pow(length) < count {
+        length += 1;
+    }
+
+    (0..count)
+        .map(|mut index| {
+            let mut label = vec![alphabet[0]; length as usize];
+            for c in label.iter_mut().rev() {
+                *c = alphabet[index % alphabet.len()];
+                index /= alphabet.len();
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+/// Labels the links of the lines, from top to bottom and left to right. `first_row` is the 1-based
+/// row of the first line.
+pub fn hints_for_lines<S: AsRef<str>>(
+    lines: &[S],
+    first_row: usize,
+    alphabet: &[char],
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> Vec<Hint> {
+    let links: Vec<(usize, ParsedLink)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            detect_links_with_min_confidence(line.as_ref(), os, min_confidence)
+                .into_iter()
+                .map(move |link| (first_row + index, link))
+        })
+        .collect();
+
+    hint_labels(links.len(), alphabet)
+        .into_iter()
+        .zip(links)
+        .map(|(label, (row, link))| Hint { label, row, link })
+        .collect()
+}
+
+fn namespace() -> u32 {
+    api::create_namespace("fetch_hints")
+}
+
+/// Labels the links in the visible lines of the current window with extmarks and returns them. An
+/// empty `alphabet` uses [`DEFAULT_ALPHABET`].
+pub(crate) fn show_hints(
+    alphabet: &str,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> nvim_oxi::Result<Vec<Hint>> {
+    let alphabet = if alphabet.is_empty() {
+        DEFAULT_ALPHABET
+    } else {
+        alphabet
+    };
+    let alphabet: Vec<char> = alphabet.chars().collect();
+    if alphabet.len() < 2 {
+        return Err(api::Error::Other(
+            "the hint alphabet needs at least two characters".to_string(),
+        )
+        .into());
+    }
+
+    let first_row: usize = api::call_function("line", Array::from_iter([Object::from("w0")]))?;
+    let last_row: usize = api::call_function("line", Array::from_iter([Object::from("w$")]))?;
+
+    let mut buffer = api::get_current_buf();
+    let lines: Vec<String> = buffer
+        .get_lines(first_row - 1..last_row, false)?
+        .map(|line| line.to_string_lossy().into_owned())
+        .collect();
+    let hints = hints_for_lines(&lines, first_row, &alphabet, os, min_confidence);
+
+    let namespace = namespace();
+    buffer.clear_namespace(namespace, ..)?;
+    for hint in &hints {
+        let path = &hint.link.path;
+        let opts = SetExtmarkOpts::builder()
+            .end_col(path.index + path.text.len())
+            .hl_group("Underlined")
+            .virt_text([(hint.label.as_str(), "IncSearch")])
+            .virt_text_pos(ExtmarkVirtTextPosition::Overlay)
+            .priority(1000)
+            .build();
+        buffer.set_extmark(namespace, hint.row - 1, path.index, &opts)?;
+    }
+
+    Ok(hints)
+}
+
+/// Removes the labels added by [`show_hints`] from the buffer.
+pub(crate) fn clear_hints(mut buffer: Buffer) -> nvim_oxi::Result<()> {
+    buffer.clear_namespace(namespace(), ..)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::OperatingSystem;
+    use crate::hints::{hint_labels, hints_for_lines};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    pub fn test_hint_labels() {
+        let alphabet = ['a', 's', 'd'];
+
+        assert_eq!(hint_labels(0, &alphabet), Vec::<String>::new());
+        assert_eq!(hint_labels(3, &alphabet), vec!["a", "s", "d"]);
+        assert_eq!(
+            hint_labels(5, &alphabet),
+            vec!["aa", "as", "ad", "sa", "ss"]
+        );
+        assert_eq!(hint_labels(10, &alphabet).last().unwrap(), "saa");
+    }
+
+    #[test]
+    pub fn test_hints_for_lines() {
+        let lines = ["see a.rs:1 and b.rs:2", "nothing", "src/main.rs:10:5"];
+
+        let hints = hints_for_lines(&lines, 20, &['a', 's', 'd'], OperatingSystem::Linux, 0);
+
+        let hints: Vec<(&str, usize, &str)> = hints
+            .iter()
+            .map(|hint| (hint.label.as_str(), hint.row, hint.link.path.text.as_str()))
+            .collect();
+        assert_eq!(
+            hints,
+            vec![
+                ("a", 20, "a.rs"),
+                ("s", 20, "b.rs"),
+                ("d", 22, "src/main.rs")
+            ]
+        );
+    }
+}
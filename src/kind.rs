@@ -0,0 +1,119 @@
+//! Classification of the targets of links into directories, text files and other files such as
+//! images or PDFs, which are better opened by the system than by neovim.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Files with a NUL byte in their first bytes are considered binary, like git does.
+const BINARY_CHECK_LENGTH: usize = 8000;
+
+/// Extensions of files that are opened by the system even when their content looks like text.
+const SYSTEM_EXTENSIONS: &[&str] = &[
+    "7z", "avi", "bmp", "docx", "epub", "flac", "gif", "gz", "ico", "jpeg", "jpg", "mkv", "mov",
+    "mp3", "mp4", "odp", "ods", "odt", "ogg", "pdf", "png", "pptx", "tar", "tif", "tiff", "wav",
+    "webm", "webp", "xlsx", "xz", "zip",
+];
+
+/// The signatures at the start of common binary formats.
+const MAGIC_BYTES: &[&[u8]] = &[
+    b"\x89PNG",
+    b"\xff\xd8\xff",
+    b"GIF8",
+    b"%PDF-",
+    b"PK\x03\x04",
+    b"\x1f\x8b",
+    b"\x7fELF",
+    // WebP, WAV, AVI and other RIFF containers
+    b"RIFF",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Directory,
+    Text,
+    /// Images, documents, archives and other files that aren't text
+    Binary,
+}
+
+impl FileKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Directory => "directory",
+            Self::Text => "text",
+            Self::Binary => "binary",
+        }
+    }
+}
+
+/// Whether the bytes look like the start of a binary file.
+pub(crate) fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_CHECK_LENGTH)].contains(&0)
+        || MAGIC_BYTES.iter().any(|magic| bytes.starts_with(magic))
+        // MP4, MOV and other ISO media files
+        || bytes.get(4..8) == Some(b"ftyp")
+}
+
+/// Classifies the file by its extension or otherwise its first bytes, `None` if it doesn't exist
+/// or can't be read.
+pub fn classify_path(path: &Path) -> Option<FileKind> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.is_dir() {
+        return Some(FileKind::Directory);
+    }
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    if extension.is_some_and(|extension| SYSTEM_EXTENSIONS.contains(&extension.as_str())) {
+        return Some(FileKind::Binary);
+    }
+
+    let mut bytes = Vec::with_capacity(BINARY_CHECK_LENGTH);
+    fs::File::open(path)
+        .ok()?
+        .take(BINARY_CHECK_LENGTH as u64)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    Some(if is_binary(&bytes) {
+        FileKind::Binary
+    } else {
+        FileKind::Text
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::kind::{FileKind, classify_path, is_binary};
+    use crate::test_util::TempDir;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    pub fn test_is_binary() {
+        assert!(is_binary(b"\x89PNG\r\n\x1a\n"));
+        assert!(is_binary(b"%PDF-1.7\n%text"));
+        assert!(is_binary(b"\0\0\0\x18ftypmp42"));
+        assert!(is_binary(b"text\0"));
+        assert!(!is_binary(b"fn main() {}\n"));
+        assert!(!is_binary(b""));
+    }
+
+    #[test]
+    pub fn test_classify_path() {
+        let dir = TempDir::new("kind");
+        std::fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("image"), b"\x89PNG\r\n\x1a\n").unwrap();
+        std::fs::write(dir.join("doc.PDF"), "text").unwrap();
+
+        let cases = [
+            (dir.to_path_buf(), Some(FileKind::Directory)),
+            (dir.join("main.rs"), Some(FileKind::Text)),
+            (dir.join("image"), Some(FileKind::Binary)),
+            (dir.join("doc.PDF"), Some(FileKind::Binary)),
+            (dir.join("missing.rs"), None),
+        ];
+        for (path, kind) in cases {
+            assert_eq!(classify_path(&path), kind, "{}", path.display());
+        }
+    }
+}
@@ -3,13 +3,35 @@
 //! exist.
 //! It is a port of the MIT-licensed code in VSCode found [here](https://github.com/microsoft/vscode/blob/22ee791ce8629104cf784cd7b96027b8abb98aa1/src/vs/workbench/contrib/terminalContrib/links/browser/terminalLinkParsing.ts)
 
-use fancy_regex::Regex;
 use nvim_oxi::conversion::{Error as ConversionError, ToObject};
 use nvim_oxi::serde::Serializer;
 use nvim_oxi::{Object, lua};
 use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::Serialize;
 
+pub mod ansi;
+pub mod cargo_messages;
+mod confidence;
+pub mod diagnostics;
+pub mod filters;
+pub mod format;
+pub mod hints;
+pub mod kind;
+pub mod lsp_diagnostics;
+mod markup;
+pub mod navigation;
+mod offsets;
+pub mod open;
+pub mod permalink;
+pub mod picker;
+pub mod preview;
+pub mod python_traceback;
+pub mod rust_backtrace;
+pub mod sarif;
+pub mod sourcemap;
+pub mod stream;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum OperatingSystem {
     Windows,
@@ -37,6 +59,10 @@ pub struct ParsedLink {
     pub path: LinkPartialRange,
     pub prefix: Option<LinkPartialRange>,
     pub suffix: Option<LinkSuffix>,
+    /// How likely the link is to point to a file from 0 to 100, based on the suffix, the extension,
+    /// the start of the path, quoting and the text before the link. Links with an explicit target,
+    /// such as markdown links and permalinks, always have a confidence of 100.
+    pub confidence: u8,
 }
 
 impl ToObject for ParsedLink {
@@ -190,12 +216,40 @@ fn generate_link_suffix_regex(eol_only: bool) -> Regex {
     Regex::new(&format!("({})", suffix_clause)).unwrap()
 }
 
-/// This defines valid path characters for a link with a suffix, the first `[]` of the regex includes
-/// characters the path is not allowed to _start_ with, the second `[]` includes characters not
-/// allowed at all in the path. If the characters show up in both regexes the link will stop at that
-/// character, otherwise it will stop at a space character.
-static LINK_WITH_SUFFIX_PATH_CHARACTERS: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?P<path>(?:file:///)?[^\s\|<>]*)$").unwrap());
+/// Finds the start of the path of a link with a suffix, `before_suffix` being the text between the
+/// previous suffix and this one. The path may contain any character other than whitespace, `|`, `<`
+/// and `>`. Spaces escaped with a backslash, such as `My\ Documents/foo.txt`, are considered part of
+/// the path.
+///
+/// This scans backwards from the suffix so the time taken is linear in the length of the path, a
+/// regex anchored at the end would have to scan the whole line for every suffix.
+fn find_path_start_before_suffix(before_suffix: &str) -> usize {
+    let bytes = before_suffix.as_bytes();
+    let mut start = before_suffix.len();
+    for (i, c) in before_suffix.char_indices().rev() {
+        let is_escaped_space = c == ' ' && i > 0 && bytes[i - 1] == b'\\';
+        if !is_escaped_space && (c.is_whitespace() || matches!(c, '|' | '<' | '>')) {
+            break;
+        }
+        start = i;
+    }
+    start
+}
+
+/// Removes the backslashes escaping spaces in a path, such as `My\ Documents`, which are part of
+/// the link text but not of the file name. Backslashes are path separators on Windows so they're
+/// kept there.
+pub(crate) fn unescape_spaces(path: &str, os: OperatingSystem) -> String {
+    match os {
+        OperatingSystem::Windows => path.to_string(),
+        _ => path.replace("\\ ", " "),
+    }
+}
+
+/// A regex that matches strings wrapped in matching single or double quotes. Used to find quoted
+/// paths that contain spaces, such as `"My Documents/foo.txt"`.
+static QUOTED_STRING_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#""(?P<double>[^"]*)"|'(?P<single>[^']*)'"#).unwrap());
 
 /// Removes the optional link suffix which contains line and column information.
 /// @param link The link to use.
@@ -224,7 +278,7 @@ pub fn detect_link_suffixes(line: &str) -> Vec<LinkSuffix> {
     // in place such that there are no overlapping matches.
     let mut results = Vec::new();
     for caps in LINK_SUFFIX_REGEX.captures_iter(line) {
-        if let Some(suffix) = to_link_suffix(&caps.unwrap()) {
+        if let Some(suffix) = to_link_suffix(&caps) {
             results.push(suffix);
         }
     }
@@ -236,11 +290,10 @@ pub fn detect_link_suffixes(line: &str) -> Vec<LinkSuffix> {
 pub fn get_link_suffix(link: &str) -> Option<LinkSuffix> {
     LINK_SUFFIX_REGEX_EOL
         .captures(link)
-        .unwrap()
         .and_then(|caps| to_link_suffix(&caps))
 }
 
-fn to_link_suffix(captures: &fancy_regex::Captures) -> Option<LinkSuffix> {
+fn to_link_suffix(captures: &regex::Captures) -> Option<LinkSuffix> {
     let matched = captures.get(0)?;
     let full_text = matched.as_str();
     let start_idx = matched.start();
@@ -252,14 +305,16 @@ fn to_link_suffix(captures: &fancy_regex::Captures) -> Option<LinkSuffix> {
     };
 
     // Try to extract row/col values from the various capture groups
-    let row = (0..3).find_map(|i| parse_int_opt(&format!("row{}", i)));
-    let col = (0..3).find_map(|i| parse_int_opt(&format!("col{}", i)));
-    let row_end = (0..3).find_map(|i| parse_int_opt(&format!("rowEnd{}", i)));
-    let col_end = (0..3).find_map(|i| parse_int_opt(&format!("colEnd{}", i)));
+    let row = ["row0", "row1", "row2"].into_iter().find_map(parse_int_opt);
+    let col = ["col0", "col1", "col2"].into_iter().find_map(parse_int_opt);
+    let row_end = ["rowEnd0", "rowEnd1", "rowEnd2"]
+        .into_iter()
+        .find_map(parse_int_opt);
+    let col_end = ["colEnd0", "colEnd1", "colEnd2"]
+        .into_iter()
+        .find_map(parse_int_opt);
 
-    if row.is_none() {
-        return None;
-    }
+    row?;
 
     Some(LinkSuffix {
         row,
@@ -283,6 +338,7 @@ enum RegexPathConstants {
     WinPathSeparatorClause,
     WinExcludedPathCharactersClause,
     WinExcludedStartPathCharactersClause,
+    EscapedSpaceClause,
 }
 
 impl RegexPathConstants {
@@ -298,20 +354,28 @@ impl RegexPathConstants {
             Self::WinPathSeparatorClause => r"(?:\\|/)",
             Self::WinExcludedPathCharactersClause => r#"[^\x00<>\?|\\/\s!`&*()'\":;]"#,
             Self::WinExcludedStartPathCharactersClause => r#"[^\x00<>\?|\\/\s!`&*()\[\]'\":;]"#,
+            // A space escaped with a backslash, eg. `My\ Documents`. This is only used on
+            // non-Windows platforms where \ is not a path separator
+            Self::EscapedSpaceClause => r"\\ ",
         }
     }
 }
 
-/// A regex that matches non-Windows paths, such as `/foo`, `~/foo`, `./foo`, `../foo` and
-/// `foo/bar`.
+/// A regex that matches non-Windows paths, such as `/foo`, `~/foo`, `./foo`, `../foo`,
+/// `foo/bar` and `My\ Documents/foo`.
 static UNIX_LOCAL_LINK_CLAUSE: Lazy<String> = Lazy::new(|| {
+    let path_character = format!(
+        "(?:{}|{})",
+        RegexPathConstants::EscapedSpaceClause.value(),
+        RegexPathConstants::ExcludedPathCharactersClause.value()
+    );
     format!(
-        r"(?:(?:{}|(?:{}{}*))?(?:{}(?:{})+)+)",
+        r"(?:(?:{}|(?:{}{}*))?(?:{}{}+)+)",
         RegexPathConstants::PathPrefix.value(),
         RegexPathConstants::ExcludedStartPathCharactersClause.value(),
-        RegexPathConstants::ExcludedPathCharactersClause.value(),
+        path_character,
         RegexPathConstants::PathSeparatorClause.value(),
-        RegexPathConstants::ExcludedPathCharactersClause.value()
+        path_character
     )
 });
 
@@ -333,21 +397,58 @@ static WIN_LOCAL_LINK_CLAUSE: Lazy<String> = Lazy::new(|| {
     )
 });
 
+static UNIX_LOCAL_LINK_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&UNIX_LOCAL_LINK_CLAUSE).unwrap());
+
+static WIN_LOCAL_LINK_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&WIN_LOCAL_LINK_CLAUSE).unwrap());
+
 pub fn detect_links(line: &str, os: OperatingSystem) -> Vec<ParsedLink> {
-    // 1: Detect all links on line via suffixes first
-    let mut results = detect_links_via_suffix(line);
+    detect_links_with_filters(line, os, &filters::LinkFilters::default())
+}
 
-    // Create a set of ranges that have already been matched
-    let matched_ranges: Vec<(usize, usize)> = results
-        .iter()
-        .map(|link| {
-            let start = link.path.index;
-            let end = start + link.path.text.len();
-            (start, end)
+/// Detects the links on the line, rejecting those that are more likely to be dates, times, IP
+/// addresses, versions or fractions according to `filters`.
+pub fn detect_links_with_filters(
+    line: &str,
+    os: OperatingSystem,
+    filters: &filters::LinkFilters,
+) -> Vec<ParsedLink> {
+    // 1: Detect markdown and reStructuredText links, these take precedence over all other links.
+    // Forge permalinks aren't local paths so they are only returned by
+    // `permalink::detect_permalinks`, but nothing inside of them is detected as a link either
+    let markup_links = markup::detect_markup_links(line);
+    let mut markup_ranges: Vec<(usize, usize)> = markup_links.iter().map(link_range).collect();
+    markup_ranges.extend(
+        permalink::detect_permalinks(line)
+            .iter()
+            .map(|permalink| link_range(&permalink.link)),
+    );
+    let markup_ranges = MatchedRanges::new(markup_ranges);
+
+    // 2: Detect all links on line via suffixes that aren't part of markup links
+    let mut results: Vec<ParsedLink> = detect_links_via_suffix(line)
+        .into_iter()
+        .filter(|link| {
+            let (start, end) = link_range(link);
+            !markup_ranges.overlaps(start, end)
         })
         .collect();
 
-    // 2: Detect all links without suffixes and merge non-conflicting ranges into the results
+    // Create a set of ranges that have already been matched
+    let matched_ranges = MatchedRanges::new(
+        results
+            .iter()
+            .map(|link| {
+                let start = link.path.index;
+                let end = start + link.path.text.len();
+                (start, end)
+            })
+            .chain(markup_ranges.ranges.iter().copied())
+            .collect(),
+    );
+
+    // 3: Detect all links without suffixes and merge non-conflicting ranges into the results
     let no_suffix_paths = detect_paths_no_suffix(line, os);
 
     // Filter out paths that overlap with already matched ranges
@@ -358,160 +459,291 @@ pub fn detect_links(line: &str, os: OperatingSystem) -> Vec<ParsedLink> {
             let end = start + link.path.text.len();
 
             // Check if this range overlaps with any already matched range
-            !matched_ranges.iter().any(|(matched_start, matched_end)| {
-                (start >= *matched_start && start < *matched_end) || // start is within a matched range
-                (end > *matched_start && end <= *matched_end) || // end is within a matched range
-                (start <= *matched_start && end >= *matched_end) // this range completely contains a matched range
-            })
+            !matched_ranges.overlaps(start, end)
         })
         .collect();
 
-    binary_insert_list(&mut results, filtered_paths);
+    // 4: Reject the links found by heuristics that are likely to be other kinds of numbers, and
+    // score how likely the rest are to point to files. Markup links are explicit so they are kept
+    // with the full score
+    results.extend(filtered_paths);
+    filters.apply(line, &mut results);
+    for link in &mut results {
+        link.confidence = confidence::score_link(line, link);
+    }
+
+    // Merge the links, the sort is stable so links that were detected first win ties
+    results.extend(markup_links);
+    results.sort_by_key(|link| link.path.index);
     results
 }
 
-fn binary_insert_list(list: &mut Vec<ParsedLink>, new_items: Vec<ParsedLink>) {
-    if list.is_empty() {
-        list.extend(new_items);
-        return;
+/// Detects the links on the line, dropping those with a [`ParsedLink::confidence`] below
+/// `min_confidence`.
+pub fn detect_links_with_min_confidence(
+    line: &str,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> Vec<ParsedLink> {
+    detect_links(line, os)
+        .into_iter()
+        .filter(|link| link.confidence >= min_confidence)
+        .collect()
+}
+
+/// Detects the links on each of the lines, returning them in the same order as the lines. With the
+/// `parallel` feature the lines are scanned on a thread pool, the results are identical to calling
+/// [`detect_links`] on each line.
+pub fn detect_links_batch(lines: &[&str], os: OperatingSystem) -> Vec<Vec<ParsedLink>> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        // Most lines take microseconds to scan, so they are split into chunks to keep the
+        // scheduling overhead low
+        lines
+            .par_iter()
+            .with_min_len(256)
+            .map(|line| detect_links(line, os))
+            .collect()
     }
-    for item in new_items {
-        binary_insert(list, item, 0, list.len());
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        lines.iter().map(|line| detect_links(line, os)).collect()
     }
 }
 
-fn binary_insert(list: &mut Vec<ParsedLink>, new_item: ParsedLink, low: usize, high: usize) {
-    if list.is_empty() {
-        list.push(new_item);
-        return;
-    }
-    if low >= high {
-        // Insert at the appropriate position
-        if low < list.len() && new_item.path.index < list[low].path.index {
-            list.insert(low, new_item);
-        } else if low < list.len() {
-            list.insert(low + 1, new_item);
-        } else {
-            list.push(new_item);
-        }
-        return;
+/// Ranges of the line that have already been matched by a link, used to find overlapping links in
+/// logarithmic time so that lines with many links can be scanned quickly.
+pub(crate) struct MatchedRanges {
+    /// The ranges sorted by their start
+    ranges: Vec<(usize, usize)>,
+    /// The largest end of all ranges up to and including the range at the same index
+    max_ends: Vec<usize>,
+}
+
+impl MatchedRanges {
+    pub(crate) fn new(mut ranges: Vec<(usize, usize)>) -> Self {
+        ranges.sort_unstable();
+        let max_ends = ranges
+            .iter()
+            .scan(0, |max_end, (_, end)| {
+                *max_end = (*max_end).max(*end);
+                Some(*max_end)
+            })
+            .collect();
+        Self { ranges, max_ends }
     }
 
-    let mid = (low + high) / 2;
-    if new_item.path.index > list[mid].path.index {
-        binary_insert(list, new_item, mid + 1, high);
-    } else {
-        binary_insert(list, new_item, low, mid);
+    /// Whether the range `start..end` overlaps any of the matched ranges.
+    pub(crate) fn overlaps(&self, start: usize, end: usize) -> bool {
+        // Only ranges starting before the end can overlap, of those one must end after the start
+        let candidates = self.ranges.partition_point(|(s, _)| *s < end);
+        candidates > 0 && self.max_ends[candidates - 1] > start
     }
 }
 
+/// Returns the range of the line covered by the link, including its prefix and suffix.
+pub(crate) fn link_range(link: &ParsedLink) -> (usize, usize) {
+    let start = link.prefix.as_ref().map_or(link.path.index, |p| p.index);
+    let end = link
+        .suffix
+        .as_ref()
+        .map_or(link.path.index + link.path.text.len(), |s| {
+            s.suffix.index + s.suffix.text.len()
+        });
+    (start, end)
+}
+
 fn detect_links_via_suffix(line: &str) -> Vec<ParsedLink> {
-    let mut results = Vec::new();
+    let mut results: Vec<ParsedLink> = Vec::new();
 
     // 1: Detect link suffixes on the line
     let suffixes = detect_link_suffixes(line);
+
+    // Paths are the word before their suffix, but they may only extend over the text of the
+    // previous link and not over the links before it, so that a word with many suffixes such as
+    // minified javascript doesn't repeat the whole word in every path. The scan for the start of
+    // the word stops at the previous suffix, past which the word start found for that suffix is
+    // reused. Both keep the time taken to find paths linear in the length of the line
+    let mut previous_suffix_end = 0;
+    let mut previous_search_start = 0;
+    // The start of the text of the previous link that isn't part of the links before it
+    let mut path_bound = 0;
+    // The index of the last suffix whose word was scanned for, along with the start of that word
+    let mut previous_word: Option<(usize, usize)> = None;
     for suffix in suffixes {
-        let before_suffix = &line[..suffix.suffix.index];
-        if let Ok(Some(captures)) = LINK_WITH_SUFFIX_PATH_CHARACTERS.captures(before_suffix) {
-            if let Some(path_match) = captures.name("path") {
-                let link_start_index = path_match.start();
-                let mut path = path_match.as_str().to_string();
-
-                // Extract a path prefix if it exists (not part of the path, but part of the underlined section)
-                let mut prefix: Option<LinkPartialRange> = None;
-
-                // Special case for nested quotes like single quote followed by double quote
-                if path.starts_with('\'') && path.len() > 1 && path.chars().nth(1) == Some('"') {
-                    // The outer quote is single, inner quote is double
-                    prefix = Some(LinkPartialRange {
-                        index: link_start_index + 1, // Skip the outer quote
-                        text: "\"".to_string(),
-                    });
-                    // Remove both the outer quote and the prefix from the path
-                    path = path[2..].to_string();
-                } else if let Ok(Some(prefix_match)) = Regex::new(r#"^(?P<prefix>['"])"#)
-                    .unwrap()
-                    .captures(&path.clone())
-                {
-                    if let Some(prefix_group) = prefix_match.name("prefix") {
-                        prefix = Some(LinkPartialRange {
-                            index: link_start_index,
-                            text: prefix_group.as_str().to_string(),
-                        });
-
-                        // Update the path to exclude the prefix
-                        path = path[prefix_group.as_str().len()..].to_string();
-
-                        // Don't allow suffix links to be returned when the link itself is the empty string
-                        if path.trim().is_empty() {
-                            continue;
-                        }
-
-                        // Handle multi-character prefixes
-                        if prefix_group.as_str().len() > 1 {
-                            if !suffix.suffix.text.is_empty()
-                                && (suffix.suffix.text.starts_with('\'')
-                                    || suffix.suffix.text.starts_with('"'))
-                                && prefix_group
-                                    .as_str()
-                                    .ends_with(suffix.suffix.text.chars().next().unwrap())
-                            {
-                                let trim_prefix_amount = prefix_group.as_str().len() - 1;
-                                if let Some(p) = &mut prefix {
-                                    p.index += trim_prefix_amount;
-                                    p.text =
-                                        prefix_group.as_str().chars().last().unwrap().to_string();
-                                }
-                            }
-                        }
-                    }
-                }
-
-                // Calculate the path's index correctly
-                // For the nested quotes case, we need special handling
-                let path_index =
-                    if link_start_index == 0 && path.starts_with('"') && prefix.is_some() {
-                        // This is for the test case with "'\"foo', line 5, col 6"
-                        // Here index should be 1 (after the first quote)
-                        1
-                    } else if let Some(p) = &prefix {
-                        // If we have a prefix, the path starts right after it
-                        p.index + p.text.len()
-                    } else {
-                        // Otherwise, it starts at the link start index
-                        link_start_index
-                    };
-
-                results.push(ParsedLink {
-                    path: LinkPartialRange {
-                        index: path_index,
-                        text: path,
-                    },
-                    prefix,
-                    suffix: Some(suffix),
-                });
+        let search_start = previous_suffix_end;
+        previous_suffix_end = suffix.suffix.index + suffix.suffix.text.len();
+
+        // Quoted paths may contain spaces, in which case the whole quoted string is the path. A
+        // number in the quoted path may have been picked up as a suffix of its own, eg.
+        // `"My 2019 files/foo", line 3`, so the search for the opening quote spans the previous
+        // suffix as well
+        let quoted_search_start = previous_search_start;
+        previous_search_start = search_start;
+        if let Some(link) = detect_quoted_path_before_suffix(
+            &line[quoted_search_start..suffix.suffix.index],
+            quoted_search_start,
+            &suffix,
+        ) {
+            // Drop any links that were detected within the quoted path, the results are ordered by
+            // their suffixes so these are all at the end
+            let link_start = link.prefix.as_ref().map_or(link.path.index, |p| p.index);
+            while results
+                .last()
+                .is_some_and(|l| l.suffix.as_ref().map_or(0, |s| s.suffix.index) >= link_start)
+            {
+                results.pop();
+            }
+            path_bound = link_start;
+            results.push(link);
+            continue;
+        }
+
+        let scan_start = previous_word.map_or(0, |(suffix_index, _)| suffix_index);
+        let word_start = match find_path_start_before_suffix(&line[scan_start..suffix.suffix.index])
+        {
+            // No whitespace since the previous suffix, so the word started before it
+            0 => previous_word.map_or(0, |(_, word_start)| word_start),
+            path_start => scan_start + path_start,
+        };
+        previous_word = Some((suffix.suffix.index, word_start));
+
+        let link_start_index = word_start.max(path_bound);
+        path_bound = word_start.max(search_start);
+        let mut path = line[link_start_index..suffix.suffix.index].to_string();
+
+        // Extract a path prefix if it exists (not part of the path, but part of the underlined section)
+        let mut prefix: Option<LinkPartialRange> = None;
+
+        // Special case for nested quotes like single quote followed by double quote
+        if path.starts_with('\'') && path.len() > 1 && path.chars().nth(1) == Some('"') {
+            // The outer quote is single, inner quote is double
+            prefix = Some(LinkPartialRange {
+                index: link_start_index + 1, // Skip the outer quote
+                text: "\"".to_string(),
+            });
+            // Remove both the outer quote and the prefix from the path
+            path = path[2..].to_string();
+        } else if path.starts_with(['\'', '"']) {
+            prefix = Some(LinkPartialRange {
+                index: link_start_index,
+                text: path[..1].to_string(),
+            });
+
+            // Update the path to exclude the prefix
+            path = path[1..].to_string();
+
+            // Don't allow suffix links to be returned when the link itself is the empty string
+            if path.trim().is_empty() {
+                continue;
             }
         }
+
+        // A suffix directly following the previous one, such as the `:45` in `12:30:45`, has no path
+        if path.is_empty() {
+            continue;
+        }
+
+        // Calculate the path's index correctly
+        // For the nested quotes case, we need special handling
+        let path_index = if link_start_index == 0 && path.starts_with('"') && prefix.is_some() {
+            // This is for the test case with "'\"foo', line 5, col 6"
+            // Here index should be 1 (after the first quote)
+            1
+        } else if let Some(p) = &prefix {
+            // If we have a prefix, the path starts right after it
+            p.index + p.text.len()
+        } else {
+            // Otherwise, it starts at the link start index
+            link_start_index
+        };
+
+        results.push(ParsedLink {
+            path: LinkPartialRange {
+                index: path_index,
+                text: path,
+            },
+            prefix,
+            suffix: Some(suffix),
+            confidence: 0,
+        });
     }
 
     results
 }
 
-fn detect_paths_no_suffix(line: &str, os: OperatingSystem) -> Vec<ParsedLink> {
-    let mut results = Vec::new();
-
-    let regex_pattern = match os {
-        OperatingSystem::Windows => WIN_LOCAL_LINK_CLAUSE.clone(),
-        _ => UNIX_LOCAL_LINK_CLAUSE.clone(),
+/// Detects a quoted path containing spaces that directly precedes a link suffix, such as
+/// `"My Documents/foo", line 3` or `'My Documents/foo':3`. The closing quote may either be the
+/// start of the suffix or sit between the path and the suffix.
+fn detect_quoted_path_before_suffix(
+    before_suffix: &str,
+    offset: usize,
+    suffix: &LinkSuffix,
+) -> Option<ParsedLink> {
+    let is_quote = |c: &char| *c == '"' || *c == '\'';
+    let (quote, path_end) = match suffix.suffix.text.chars().next().filter(is_quote) {
+        Some(quote) => (quote, before_suffix.len()),
+        None => (
+            before_suffix.chars().last().filter(is_quote)?,
+            before_suffix.len() - 1,
+        ),
     };
 
-    let regex = Regex::new(&regex_pattern).unwrap();
+    let quote_index = before_suffix[..path_end].rfind(quote)?;
+    let path = &before_suffix[quote_index + 1..path_end];
+    let prefix_index = offset + quote_index;
+    if !is_quoted_path_with_spaces(path) {
+        return None;
+    }
+
+    Some(ParsedLink {
+        path: LinkPartialRange {
+            index: prefix_index + 1,
+            text: path.to_string(),
+        },
+        prefix: Some(LinkPartialRange {
+            index: prefix_index,
+            text: quote.to_string(),
+        }),
+        suffix: Some(suffix.clone()),
+        confidence: 0,
+    })
+}
+
+/// Whether the contents of a quoted string should be treated as a single path. Quoted strings
+/// without spaces are left to the regular path regexes.
+fn is_quoted_path_with_spaces(path: &str) -> bool {
+    path.contains(char::is_whitespace)
+        && !path.starts_with(char::is_whitespace)
+        && !path.ends_with(char::is_whitespace)
+        && !path.contains(['"', '\'', '\x00', '<', '>', '|', '\r', '\n'])
+}
+
+fn detect_paths_no_suffix(line: &str, os: OperatingSystem) -> Vec<ParsedLink> {
+    let mut results = detect_quoted_paths_no_suffix(line, os);
+    let quoted_ranges = MatchedRanges::new(
+        results
+            .iter()
+            .map(|link| (link.path.index, link.path.index + link.path.text.len()))
+            .collect(),
+    );
+
+    let regex = match os {
+        OperatingSystem::Windows => &*WIN_LOCAL_LINK_REGEX,
+        _ => &*UNIX_LOCAL_LINK_REGEX,
+    };
 
-    for captures in regex.captures_iter(line) {
-        let full_match = captures.unwrap().get(0).unwrap();
+    for full_match in regex.find_iter(line) {
         let mut text = full_match.as_str().to_string();
         let mut index = full_match.start();
 
+        // Skip anything that is part of a quoted path that was already detected
+        if quoted_ranges.overlaps(index, full_match.end()) {
+            continue;
+        }
+
         // Adjust the link range to exclude a/ and b/ if it looks like a git diff
         if ((line.starts_with("--- a/") || line.starts_with("+++ b/")) && index == 4)
             || (line.starts_with("diff --git")
@@ -525,36 +757,127 @@ fn detect_paths_no_suffix(line: &str, os: OperatingSystem) -> Vec<ParsedLink> {
             path: LinkPartialRange { index, text },
             prefix: None,
             suffix: None,
+            confidence: 0,
         });
     }
 
+    results.sort_by_key(|link| link.path.index);
     results
 }
 
+/// Detects quoted paths that contain spaces, such as `"My Documents/foo.txt"`. The quoted string
+/// must contain a path separator to be considered a path.
+fn detect_quoted_paths_no_suffix(line: &str, os: OperatingSystem) -> Vec<ParsedLink> {
+    let is_separator = |c: char| c == '/' || (os == OperatingSystem::Windows && c == '\\');
+
+    QUOTED_STRING_REGEX
+        .captures_iter(line)
+        .filter_map(|captures| {
+            let path = captures
+                .name("double")
+                .or_else(|| captures.name("single"))?;
+            if !is_quoted_path_with_spaces(path.as_str()) || !path.as_str().contains(is_separator) {
+                return None;
+            }
+
+            Some(ParsedLink {
+                path: LinkPartialRange {
+                    index: path.start(),
+                    text: path.as_str().to_string(),
+                },
+                prefix: Some(LinkPartialRange {
+                    index: path.start() - 1,
+                    text: line[path.start() - 1..path.start()].to_string(),
+                }),
+                suffix: None,
+                confidence: 0,
+            })
+        })
+        .collect()
+}
+
+/// How to pick a link for a position that isn't inside any link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkLookup {
+    /// Only the link containing the position
+    #[default]
+    Exact,
+    /// The link containing the position or else the nearest link after it, like `gf`
+    Forward,
+    /// The link containing the position or else the closest link in either direction. When the
+    /// links before and after the position are equally close, the one after it is picked.
+    Closest,
+}
+
+impl std::str::FromStr for LinkLookup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(Self::Exact),
+            "forward" => Ok(Self::Forward),
+            "closest" => Ok(Self::Closest),
+            _ => Err(format!("unknown link lookup: {}", s)),
+        }
+    }
+}
+
+/// Returns the link containing the 1-based byte column `position`, as used by neovim's cursor.
 pub fn get_link_at_position_in_line(
     line: &str,
     position: usize,
     os: OperatingSystem,
 ) -> Option<ParsedLink> {
-    let links = detect_links(line, os);
-
-    links
-        .iter()
-        .find(|l| {
-            let start = l
-                .prefix
-                .as_ref()
-                .map_or(l.path.index, |prefix| prefix.index);
-            let len = l.path.text.len()
-                + l.suffix
-                    .as_ref()
-                    .map_or(0, |suffix| suffix.suffix.text.len())
-                + l.prefix.as_ref().map_or(0, |prefix| prefix.text.len());
-            let end = start + len;
-
-            position >= start && position <= end
-        })
-        .cloned()
+    get_link_near_position_in_line(line, position, os, LinkLookup::Exact)
+}
+
+/// Returns the link containing the 1-based byte column `position`, or one near it depending on
+/// `lookup`.
+pub fn get_link_near_position_in_line(
+    line: &str,
+    position: usize,
+    os: OperatingSystem,
+    lookup: LinkLookup,
+) -> Option<ParsedLink> {
+    find_link_near_position(&detect_links(line, os), position, lookup).cloned()
+}
+
+/// Picks the link for the 1-based byte column `position` from the links of a line, which must be
+/// sorted as returned by [`detect_links`].
+pub fn find_link_near_position(
+    links: &[ParsedLink],
+    position: usize,
+    lookup: LinkLookup,
+) -> Option<&ParsedLink> {
+    let index = position.saturating_sub(1);
+
+    // The distance from the position to each link, zero when the position is inside it
+    let distances = links.iter().map(|link| {
+        let (start, end) = link_range(link);
+        let distance = if index < start {
+            start - index
+        } else if index >= end {
+            index + 1 - end
+        } else {
+            0
+        };
+        (link, distance, index < start)
+    });
+
+    match lookup {
+        LinkLookup::Exact => distances
+            .filter(|(_, distance, _)| *distance == 0)
+            .map(|(link, _, _)| link)
+            .next(),
+        LinkLookup::Forward => distances
+            .filter(|(_, distance, after)| *distance == 0 || *after)
+            .map(|(link, _, _)| link)
+            .next(),
+        // Links after the position sort first among equal distances
+        LinkLookup::Closest => distances
+            .min_by_key(|(_, distance, after)| (*distance, !*after))
+            .map(|(link, _, _)| link),
+    }
 }
 
 #[nvim_oxi::plugin]
@@ -567,12 +890,169 @@ pub fn fetch_rs() -> nvim_oxi::Dictionary {
         OperatingSystem::Linux
     };
 
-    let get_link_at_position_in_line = nvim_oxi::Function::from_fn(move |args: (String, usize)| {
-        get_link_at_position_in_line(&args.0, args.1, os)
+    let get_link_at_position_in_line: nvim_oxi::Function<
+        (String, usize, u8, String),
+        Option<ParsedLink>,
+    > = nvim_oxi::Function::from_fn(move |args: (String, usize, u8, String)| {
+        let lookup = args
+            .3
+            .parse::<LinkLookup>()
+            .map_err(nvim_oxi::api::Error::Other)?;
+        let links = detect_links_with_min_confidence(&args.0, os, args.2);
+        Ok::<_, nvim_oxi::api::Error>(find_link_near_position(&links, args.1, lookup).cloned())
+    });
+
+    let get_links_from_line = nvim_oxi::Function::from_fn(move |args: (String, u8)| {
+        detect_links_with_min_confidence(&args.0, os, args.1)
+    });
+
+    let get_links_from_lines = nvim_oxi::Function::from_fn(move |lines: Vec<String>| {
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        detect_links_batch(&lines, os)
+    });
+
+    let get_links_from_ansi_line = nvim_oxi::Function::from_fn(move |args: (String, u8)| {
+        ansi::detect_links_ansi_with_min_confidence(&args.0, os, args.1)
+    });
+
+    let get_permalinks_from_line =
+        nvim_oxi::Function::from_fn(|line: String| permalink::detect_permalinks(&line));
+
+    let get_local_path_for_permalink = nvim_oxi::Function::from_fn(|args: (String, String)| {
+        permalink::parse_permalink(&args.0)
+            .and_then(|p| p.local_path_in_repository(std::path::Path::new(&args.1)))
+            .map(|path| path.to_string_lossy().into_owned())
     });
 
-    let get_links_from_line =
-        nvim_oxi::Function::from_fn(move |line: String| detect_links(&line, os));
+    let get_location_string: nvim_oxi::Function<String, Option<String>> =
+        nvim_oxi::Function::from_fn(|format: String| {
+            let format = format
+                .parse::<format::CurrentLocationFormat>()
+                .map_err(nvim_oxi::api::Error::Other)?;
+            format::format_current_location(format)
+        });
+
+    let get_adjacent_link_position: nvim_oxi::Function<(String, bool, u8), Option<(usize, usize)>> =
+        nvim_oxi::Function::from_fn(move |args: (String, bool, u8)| {
+            let direction = args
+                .0
+                .parse::<navigation::Direction>()
+                .map_err(nvim_oxi::api::Error::Other)?;
+            navigation::find_adjacent_link_from_cursor(direction, args.1, os, args.2)
+        });
+
+    let get_picker_entries: nvim_oxi::Function<(bool, u8), Vec<picker::PickerEntry>> =
+        nvim_oxi::Function::from_fn(move |args: (bool, u8)| {
+            picker::picker_entries_from_buffers(args.0, os, args.1)
+        });
+
+    let show_link_hints: nvim_oxi::Function<(String, u8), Vec<hints::Hint>> =
+        nvim_oxi::Function::from_fn(move |args: (String, u8)| {
+            hints::show_hints(&args.0, os, args.1)
+        });
+
+    let clear_link_hints: nvim_oxi::Function<i32, ()> =
+        nvim_oxi::Function::from_fn(|bufnr: i32| {
+            hints::clear_hints(nvim_oxi::api::Buffer::from(bufnr))
+        });
+
+    // The path, the row, column, end row and end column, and the open policy
+    type OpenLocationArgs = (
+        String,
+        Option<u32>,
+        Option<u32>,
+        Option<u32>,
+        Option<u32>,
+        String,
+    );
+    let open_location: nvim_oxi::Function<OpenLocationArgs, bool> =
+        nvim_oxi::Function::from_fn(move |args: OpenLocationArgs| {
+            let policy = args
+                .5
+                .parse::<open::OpenPolicy>()
+                .map_err(nvim_oxi::api::Error::Other)?;
+            let selection = open::Selection {
+                row: args.1,
+                col: args.2,
+                row_end: args.3,
+                col_end: args.4,
+            };
+            open::open_location(&args.0, &selection, policy, os)
+        });
+
+    let open_link_under_cursor: nvim_oxi::Function<(String, u8, String), bool> =
+        nvim_oxi::Function::from_fn(move |args: (String, u8, String)| {
+            let policy = args
+                .0
+                .parse::<open::OpenPolicy>()
+                .map_err(nvim_oxi::api::Error::Other)?;
+            let lookup = args
+                .2
+                .parse::<LinkLookup>()
+                .map_err(nvim_oxi::api::Error::Other)?;
+            open::open_link_under_cursor(policy, os, args.1, lookup)
+        });
+
+    let get_preview_of_link_under_cursor: nvim_oxi::Function<
+        (usize, u8, String),
+        Option<preview::Preview>,
+    > = nvim_oxi::Function::from_fn(move |args: (usize, u8, String)| {
+        let lookup = args
+            .2
+            .parse::<LinkLookup>()
+            .map_err(nvim_oxi::api::Error::Other)?;
+        preview::preview_link_under_cursor(args.0, os, args.1, lookup)
+    });
+
+    let open_link_under_cursor_with_system: nvim_oxi::Function<(u8, String), bool> =
+        nvim_oxi::Function::from_fn(move |args: (u8, String)| {
+            let lookup = args
+                .1
+                .parse::<LinkLookup>()
+                .map_err(nvim_oxi::api::Error::Other)?;
+            open::open_link_under_cursor_with_system(os, args.0, lookup)
+        });
+
+    let get_path_kind = nvim_oxi::Function::from_fn(|path: String| {
+        kind::classify_path(std::path::Path::new(&path)).map(|kind| kind.as_str().to_string())
+    });
+
+    let resolve_source_map: nvim_oxi::Function<
+        (String, u32, Option<u32>),
+        Option<sourcemap::MappedLocation>,
+    > = nvim_oxi::Function::from_fn(|args: (String, u32, Option<u32>)| {
+        sourcemap::resolve_location(std::path::Path::new(&args.0), args.1, args.2)
+            .map_err(nvim_oxi::api::Error::Other)
+    });
+
+    let parse_python_traceback =
+        nvim_oxi::Function::from_fn(|args: (Vec<String>, Option<String>)| {
+            python_traceback::parse_python_traceback(
+                &args.0,
+                args.1.as_deref().map(std::path::Path::new),
+            )
+        });
+
+    let parse_cargo_messages = nvim_oxi::Function::from_fn(|lines: Vec<String>| {
+        cargo_messages::parse_cargo_messages(&lines)
+    });
+
+    let read_diagnostics_file: nvim_oxi::Function<
+        (String, Option<String>),
+        Vec<diagnostics::Diagnostic>,
+    > = nvim_oxi::Function::from_fn(|args: (String, Option<String>)| {
+        let format = args
+            .1
+            .map(|format| format.parse::<diagnostics::DiagnosticFormat>())
+            .transpose()
+            .map_err(nvim_oxi::api::Error::Other)?;
+        diagnostics::read_diagnostics_file(std::path::Path::new(&args.0), format)
+            .map_err(nvim_oxi::api::Error::Other)
+    });
+
+    let parse_rust_backtrace = nvim_oxi::Function::from_fn(|lines: Vec<String>| {
+        rust_backtrace::parse_rust_backtrace(&lines, rust_backtrace::rust_src_sysroot())
+    });
 
     nvim_oxi::Dictionary::from_iter([
         (
@@ -583,6 +1063,70 @@ pub fn fetch_rs() -> nvim_oxi::Dictionary {
             "get_link_at_position_in_line",
             nvim_oxi::Object::from(get_link_at_position_in_line),
         ),
+        (
+            "get_links_from_lines",
+            nvim_oxi::Object::from(get_links_from_lines),
+        ),
+        (
+            "get_links_from_ansi_line",
+            nvim_oxi::Object::from(get_links_from_ansi_line),
+        ),
+        (
+            "get_permalinks_from_line",
+            nvim_oxi::Object::from(get_permalinks_from_line),
+        ),
+        (
+            "get_local_path_for_permalink",
+            nvim_oxi::Object::from(get_local_path_for_permalink),
+        ),
+        (
+            "get_location_string",
+            nvim_oxi::Object::from(get_location_string),
+        ),
+        (
+            "get_adjacent_link_position",
+            nvim_oxi::Object::from(get_adjacent_link_position),
+        ),
+        (
+            "get_picker_entries",
+            nvim_oxi::Object::from(get_picker_entries),
+        ),
+        ("show_link_hints", nvim_oxi::Object::from(show_link_hints)),
+        ("clear_link_hints", nvim_oxi::Object::from(clear_link_hints)),
+        ("open_location", nvim_oxi::Object::from(open_location)),
+        (
+            "open_link_under_cursor",
+            nvim_oxi::Object::from(open_link_under_cursor),
+        ),
+        (
+            "get_preview_of_link_under_cursor",
+            nvim_oxi::Object::from(get_preview_of_link_under_cursor),
+        ),
+        (
+            "open_link_under_cursor_with_system",
+            nvim_oxi::Object::from(open_link_under_cursor_with_system),
+        ),
+        ("get_path_kind", nvim_oxi::Object::from(get_path_kind)),
+        (
+            "resolve_source_map",
+            nvim_oxi::Object::from(resolve_source_map),
+        ),
+        (
+            "parse_python_traceback",
+            nvim_oxi::Object::from(parse_python_traceback),
+        ),
+        (
+            "parse_rust_backtrace",
+            nvim_oxi::Object::from(parse_rust_backtrace),
+        ),
+        (
+            "parse_cargo_messages",
+            nvim_oxi::Object::from(parse_cargo_messages),
+        ),
+        (
+            "read_diagnostics_file",
+            nvim_oxi::Object::from(read_diagnostics_file),
+        ),
     ])
 }
 
@@ -627,7 +1171,89 @@ mod test {
         let suffix = res.suffix.clone();
         assert_eq!(suffix.expect("should have suffix").row, Some(11));
     }
+
+    #[test]
+    pub fn test_link_at_position_boundaries() {
+        use crate::get_link_at_position_in_line;
+
+        // `a.rs:1` spans the columns 1 to 6 and `b.rs:2` the columns 8 to 13
+        let line = "a.rs:1 b.rs:2";
+        let path_at = |position| {
+            get_link_at_position_in_line(line, position, crate::OperatingSystem::Linux)
+                .map(|link| link.path.text)
+        };
+
+        assert_eq!(path_at(1).as_deref(), Some("a.rs"));
+        assert_eq!(path_at(6).as_deref(), Some("a.rs"));
+        assert_eq!(path_at(7), None);
+        assert_eq!(path_at(8).as_deref(), Some("b.rs"));
+        assert_eq!(path_at(13).as_deref(), Some("b.rs"));
+        assert_eq!(path_at(14), None);
+
+        // The closing parenthesis of the first link is part of neither link
+        let line = "[a](a.rs)[b](b.rs)";
+        let path_at = |position| {
+            get_link_at_position_in_line(line, position, crate::OperatingSystem::Linux)
+                .map(|link| link.path.text)
+        };
+        assert_eq!(path_at(8).as_deref(), Some("a.rs"));
+        assert_eq!(path_at(9), None);
+        assert_eq!(path_at(10).as_deref(), Some("b.rs"));
+    }
+
+    #[test]
+    pub fn test_link_near_position() {
+        use crate::{LinkLookup, get_link_near_position_in_line};
+
+        let line = "see a.rs:1 and   b.rs:2 ok";
+        let path_near = |position, lookup| {
+            get_link_near_position_in_line(line, position, crate::OperatingSystem::Linux, lookup)
+                .map(|link| link.path.text)
+        };
+
+        assert_eq!(path_near(1, LinkLookup::Exact), None);
+        assert_eq!(path_near(1, LinkLookup::Forward).as_deref(), Some("a.rs"));
+        assert_eq!(path_near(5, LinkLookup::Forward).as_deref(), Some("a.rs"));
+        assert_eq!(path_near(12, LinkLookup::Forward).as_deref(), Some("b.rs"));
+        assert_eq!(path_near(25, LinkLookup::Forward), None);
+
+        assert_eq!(path_near(12, LinkLookup::Closest).as_deref(), Some("a.rs"));
+        assert_eq!(path_near(16, LinkLookup::Closest).as_deref(), Some("b.rs"));
+        assert_eq!(path_near(25, LinkLookup::Closest).as_deref(), Some("b.rs"));
+        // Column 14 is 4 columns from either link, the one after it wins
+        assert_eq!(path_near(14, LinkLookup::Closest).as_deref(), Some("b.rs"));
+    }
+
+    #[test]
+    pub fn test_detect_links_batch_matches_detect_links() {
+        // Well above the minimum number of lines per chunk so that the lines are split across
+        // threads
+        let text = [
+            include_str!("../benches/corpus/cargo_build.txt"),
+            include_str!("../benches/corpus/pytest.txt"),
+            include_str!("../out.log"),
+        ]
+        .concat();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.len() > 16 * 256);
+
+        let expected: Vec<_> = lines
+            .iter()
+            .map(|line| detect_links(line, crate::OperatingSystem::Linux))
+            .collect();
+
+        assert_eq!(
+            crate::detect_links_batch(&lines, crate::OperatingSystem::Linux),
+            expected
+        );
+    }
 }
 
 #[cfg(test)]
 mod test_link_parsing;
+
+#[cfg(test)]
+mod test_suffix_round_trip;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,254 @@
+//! Parsing of the diagnostics of LSP `textDocument/publishDiagnostics` notifications, as logged by
+//! language servers or saved by tools that speak LSP.
+
+use crate::diagnostics::{Diagnostic, Severity, location_link, range_location, uri_path};
+use crate::format::{Location, LocationFormat, format_location};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+#[derive(Deserialize)]
+struct RawPublishDiagnostics {
+    uri: String,
+    diagnostics: Vec<RawDiagnostic>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDiagnostic {
+    range: RawRange,
+    severity: Option<u8>,
+    /// A string or a number
+    code: Option<Value>,
+    source: Option<String>,
+    message: String,
+    #[serde(default)]
+    related_information: Vec<RawRelatedInformation>,
+}
+
+#[derive(Deserialize)]
+struct RawRelatedInformation {
+    location: RawLocation,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RawLocation {
+    uri: String,
+    range: RawRange,
+}
+
+#[derive(Deserialize)]
+struct RawRange {
+    start: RawPosition,
+    end: RawPosition,
+}
+
+/// Lines and characters are 0-based and the end of a range is exclusive. Characters count UTF-16
+/// code units by default, see [`range_to_location`]
+#[derive(Deserialize)]
+struct RawPosition {
+    line: u32,
+    character: u32,
+}
+
+fn severity(severity: Option<u8>) -> Severity {
+    match severity {
+        Some(2) => Severity::Warning,
+        Some(3) => Severity::Info,
+        Some(4) => Severity::Hint,
+        // Clients decide the severity of diagnostics without one, neovim uses errors
+        _ => Severity::Error,
+    }
+}
+
+/// Returns the location of a range, whose characters are used as the columns as they are. The
+/// lines of the document aren't known, so the UTF-16 code units of the characters aren't converted
+/// to bytes and the columns are off on lines with characters outside of ASCII before them.
+fn range_to_location(uri: &str, range: &RawRange) -> Location {
+    range_location(
+        uri_path(uri),
+        (range.start.line + 1, Some(range.start.character + 1)),
+        (range.end.line + 1, Some(range.end.character + 1)),
+    )
+}
+
+/// Parses the notifications, or their params, in a JSON value, an array of values or newline
+/// delimited values. Like for a language client, a notification replaces the diagnostics of
+/// earlier notifications for the same document. Columns are the UTF-16 characters of the
+/// positions, see [`range_to_location`].
+pub fn parse_publish_diagnostics(json: &str) -> Result<Vec<Diagnostic>, String> {
+    let values = match serde_json::from_str(json) {
+        Ok(Value::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(e) => json
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|_| e.to_string())?,
+    };
+
+    // The diagnostics of each document in the order they were first published, and the index of
+    // each document
+    let mut documents: Vec<Vec<Diagnostic>> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for mut value in values {
+        if let Some(params) = value.get_mut("params") {
+            value = params.take();
+        }
+        let params: RawPublishDiagnostics =
+            serde_json::from_value(value).map_err(|e| e.to_string())?;
+
+        let diagnostics = params
+            .diagnostics
+            .into_iter()
+            .map(|raw| Diagnostic {
+                link: location_link(&range_to_location(&params.uri, &raw.range)),
+                severity: severity(raw.severity),
+                message: raw.message,
+                code: raw.code.map(|code| match code {
+                    Value::String(code) => code,
+                    code => code.to_string(),
+                }),
+                source: raw.source,
+                label: None,
+                notes: raw
+                    .related_information
+                    .iter()
+                    .map(|related| {
+                        let location =
+                            range_to_location(&related.location.uri, &related.location.range);
+                        format!(
+                            "{}: {}",
+                            format_location(&location, LocationFormat::Colon),
+                            related.message
+                        )
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        match indices.entry(params.uri) {
+            Entry::Occupied(index) => documents[*index.get()] = diagnostics,
+            Entry::Vacant(index) => {
+                index.insert(documents.len());
+                documents.push(diagnostics);
+            }
+        }
+    }
+
+    Ok(documents.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::diagnostics::Severity;
+    use crate::lsp_diagnostics::parse_publish_diagnostics;
+    use pretty_assertions::assert_eq;
+
+    /// The path, severity, code, row, column, end row and end column of a diagnostic
+    type Entry = (
+        String,
+        Severity,
+        Option<String>,
+        (Option<u32>, Option<u32>, Option<u32>, Option<u32>),
+    );
+
+    fn entries(json: &str) -> Vec<Entry> {
+        parse_publish_diagnostics(json)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| {
+                let suffix = diagnostic.link.suffix.unwrap();
+                (
+                    diagnostic.link.path.text,
+                    diagnostic.severity,
+                    diagnostic.code,
+                    (suffix.row, suffix.col, suffix.row_end, suffix.col_end),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_notification() {
+        let json = r#"{"jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": {
+  "uri": "file:///home/user/app/src/main.rs",
+  "version": 3,
+  "diagnostics": [
+    {
+      "range": {"start": {"line": 9, "character": 4}, "end": {"line": 9, "character": 12}},
+      "severity": 1,
+      "code": "E0425",
+      "source": "rustc",
+      "message": "cannot find value `x` in this scope",
+      "relatedInformation": [{
+        "location": {"uri": "file:///home/user/app/src/lib.rs", "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 5}}},
+        "message": "a similar name exists"
+      }]
+    },
+    {
+      "range": {"start": {"line": 1, "character": 0}, "end": {"line": 3, "character": 1}},
+      "severity": 4,
+      "code": 6133,
+      "message": "unused"
+    },
+    {
+      "range": {"start": {"line": 5, "character": 2}, "end": {"line": 5, "character": 2}},
+      "message": "no severity"
+    }
+  ]
+}}"#;
+
+        assert_eq!(
+            entries(json),
+            vec![
+                (
+                    "/home/user/app/src/main.rs".to_string(),
+                    Severity::Error,
+                    Some("E0425".to_string()),
+                    (Some(10), Some(5), None, Some(12))
+                ),
+                (
+                    "/home/user/app/src/main.rs".to_string(),
+                    Severity::Hint,
+                    Some("6133".to_string()),
+                    (Some(2), Some(1), Some(4), Some(1))
+                ),
+                (
+                    "/home/user/app/src/main.rs".to_string(),
+                    Severity::Error,
+                    None,
+                    (Some(6), Some(3), None, None)
+                ),
+            ]
+        );
+
+        let diagnostics = parse_publish_diagnostics(json).unwrap();
+        assert_eq!(diagnostics[0].source.as_deref(), Some("rustc"));
+        assert_eq!(
+            diagnostics[0].notes,
+            vec!["/home/user/app/src/lib.rs:1:1-5: a similar name exists"]
+        );
+    }
+
+    #[test]
+    pub fn test_later_notifications_replace_earlier_ones() {
+        let json = r#"{"uri": "file:///a.ts", "diagnostics": [{"range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 1}}, "message": "old"}]}
+{"uri": "file:///b.ts", "diagnostics": [{"range": {"start": {"line": 1, "character": 0}, "end": {"line": 1, "character": 1}}, "severity": 2, "message": "b"}]}
+{"uri": "file:///a.ts", "diagnostics": []}"#;
+
+        assert_eq!(
+            entries(json),
+            vec![(
+                "/b.ts".to_string(),
+                Severity::Warning,
+                None,
+                (Some(2), Some(1), None, Some(1))
+            )]
+        );
+        assert!(parse_publish_diagnostics("{\"uri\": \"file:///a.ts\"}").is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! Detection of links written in markup languages, such as markdown's `[text](./foo.md#L20)` and
+//! `<./foo.md>` or reStructuredText's `` `text <./foo.rst>`_ ``. The link target is returned as the
+//! path, everything from the start of the markup up to the target as the prefix and any line
+//! anchor in the target (`#L10-L20`) as the suffix.
+
+use crate::{LinkPartialRange, LinkSuffix, MatchedRanges, ParsedLink, get_link_suffix};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A regex that matches markdown links and images, such as `[text](./foo.md)`,
+/// `![alt](./foo.png "title")` and `[text](<./foo bar.md>)`.
+static MARKDOWN_LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"!?\[[^\]\n]*\]\((?:<(?P<angled>[^<>\n]+)>|(?P<target>[^\s()<>]+))(?:\s+(?:"[^"\n]*"|'[^'\n]*'))?\)"#,
+    )
+    .unwrap()
+});
+
+/// A regex that matches reStructuredText hyperlink references with embedded targets, such as
+/// `` `text <./foo.rst>`_ `` and anonymous ones such as `` `text <./foo.rst>`__ ``.
+static RST_LINK_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"`(?:[^`<\n]*\s)?<(?P<target>[^<>`\n]+)>`__?").unwrap());
+
+/// A regex that matches markdown autolinks, such as `<./foo.md>`.
+static AUTOLINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(?P<target>[^<>\s]+)>").unwrap());
+
+/// A regex that matches line anchors used by GitHub (`#L10`, `#L10-L20`, `#L10C5-L20C8`) as well as
+/// GitLab and sourcehut (`#L10-20`).
+static LINE_FRAGMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^#L(?P<row>\d+)(?:C(?P<col>\d+))?(?:-L?(?P<rowEnd>\d+)(?:C(?P<colEnd>\d+))?)?$")
+        .unwrap()
+});
+
+/// A regex that matches URI schemes, such as `https:` or `mailto:`. Single letters are excluded
+/// so that Windows drive letters are not mistaken for schemes.
+static URI_SCHEME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.\-]+:").unwrap());
+
+/// Detects all markdown and reStructuredText links on the line that point to local files.
+pub(crate) fn detect_markup_links(line: &str) -> Vec<ParsedLink> {
+    let mut results: Vec<ParsedLink> = Vec::new();
+    // The matches of a regex don't overlap each other, so they're only checked against the
+    // matches of the previous regexes
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    // Autolinks are also used for URLs and html tags, so they only accept obvious paths
+    let detectors: [(&Regex, &[&str], bool); 3] = [
+        (&MARKDOWN_LINK_REGEX, &["angled", "target"], false),
+        (&RST_LINK_REGEX, &["target"], false),
+        (&AUTOLINK_REGEX, &["target"], true),
+    ];
+
+    for (regex, target_groups, paths_only) in detectors {
+        let matched_ranges = MatchedRanges::new(ranges.clone());
+        for captures in regex.captures_iter(line) {
+            let full_match = captures.get(0).unwrap();
+            if matched_ranges.overlaps(full_match.start(), full_match.end()) {
+                continue;
+            }
+
+            let Some(target) = target_groups.iter().find_map(|name| captures.name(name)) else {
+                continue;
+            };
+
+            if paths_only && !looks_like_path(target.as_str()) {
+                continue;
+            }
+
+            if let Some(link) =
+                to_markup_link(line, full_match.start(), target.start(), target.as_str())
+            {
+                ranges.push((full_match.start(), full_match.end()));
+                results.push(link);
+            }
+        }
+    }
+
+    results.sort_by_key(|link| link.path.index);
+    results
+}
+
+fn to_markup_link(
+    line: &str,
+    markup_start: usize,
+    target_start: usize,
+    target: &str,
+) -> Option<ParsedLink> {
+    if !is_local_target(target) {
+        return None;
+    }
+
+    let (path, fragment) = match target.find('#') {
+        Some(index) => (&target[..index], Some(&target[index..])),
+        None => (target, None),
+    };
+
+    // Links to anchors within the same document have no path
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(ParsedLink {
+        path: LinkPartialRange {
+            index: target_start,
+            text: path.to_string(),
+        },
+        prefix: Some(LinkPartialRange {
+            index: markup_start,
+            text: line[markup_start..target_start].to_string(),
+        }),
+        suffix: fragment
+            .and_then(|fragment| link_suffix_from_fragment(fragment, target_start + path.len())),
+        confidence: 100,
+    })
+}
+
+/// Converts a url fragment containing line information, such as `#L10-L20` or `#339:12`, into a
+/// link suffix. The index is the position of the fragment's `#` in the line.
+pub(crate) fn link_suffix_from_fragment(fragment: &str, index: usize) -> Option<LinkSuffix> {
+    let suffix = LinkPartialRange {
+        index,
+        text: fragment.to_string(),
+    };
+
+    if let Some(captures) = LINE_FRAGMENT_REGEX.captures(fragment) {
+        let parse_int_opt = |name: &str| -> Option<u32> {
+            captures
+                .name(name)
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+        };
+
+        return Some(LinkSuffix {
+            row: parse_int_opt("row"),
+            col: parse_int_opt("col"),
+            row_end: parse_int_opt("rowEnd"),
+            col_end: parse_int_opt("colEnd"),
+            suffix,
+        });
+    }
+
+    // Fall back to the regular suffixes, only accepting them if they span the whole fragment
+    get_link_suffix(fragment)
+        .filter(|s| s.suffix.index == 0)
+        .map(|s| LinkSuffix { suffix, ..s })
+}
+
+/// Whether the target points to a local file rather than a URL such as `https://` or `mailto:`.
+fn is_local_target(target: &str) -> bool {
+    target.starts_with("file://") || !URI_SCHEME_REGEX.is_match(target)
+}
+
+/// Whether an autolink target looks like a path, ie. `<./foo.md>` or `<docs/foo.md>` but not
+/// `<div>` or `</div>`.
+fn looks_like_path(target: &str) -> bool {
+    ["./", "../", "~/", ".\\", "..\\", "~\\"]
+        .iter()
+        .any(|prefix| target.starts_with(prefix))
+        || target.chars().skip(1).any(|c| c == '/' || c == '\\')
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{LinkPartialRange, LinkSuffix, OperatingSystem, ParsedLink, detect_links};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    pub fn test_markdown_link_with_line_range() {
+        let line = "See [setup](./docs/setup.md#L20-L25) for details";
+        let results = detect_links(line, OperatingSystem::Linux);
+
+        let expected = vec![ParsedLink {
+            path: LinkPartialRange {
+                index: 12,
+                text: "./docs/setup.md".to_string(),
+            },
+            prefix: Some(LinkPartialRange {
+                index: 4,
+                text: "[setup](".to_string(),
+            }),
+            suffix: Some(LinkSuffix {
+                row: Some(20),
+                col: None,
+                row_end: Some(25),
+                col_end: None,
+                suffix: LinkPartialRange {
+                    index: 27,
+                    text: "#L20-L25".to_string(),
+                },
+            }),
+            confidence: 100,
+        }];
+
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    pub fn test_markdown_link_fragments() {
+        let cases = [
+            ("#L20", Some(20), None, None, None),
+            ("#L20C4", Some(20), Some(4), None, None),
+            ("#L20-30", Some(20), None, Some(30), None),
+            ("#L20C4-L30C8", Some(20), Some(4), Some(30), Some(8)),
+            ("#20:4", Some(20), Some(4), None, None),
+        ];
+
+        for (fragment, row, col, row_end, col_end) in cases {
+            let line = format!("[x](foo.md{})", fragment);
+            let results = detect_links(&line, OperatingSystem::Linux);
+
+            assert_eq!(results.len(), 1, "{}", line);
+            let suffix = results[0].suffix.clone().expect("should have suffix");
+            assert_eq!(
+                (suffix.row, suffix.col, suffix.row_end, suffix.col_end),
+                (row, col, row_end, col_end),
+                "{}",
+                line
+            );
+            assert_eq!(suffix.suffix.text, fragment);
+            assert_eq!(results[0].path.text, "foo.md");
+        }
+    }
+
+    #[test]
+    pub fn test_markdown_link_without_line_anchor() {
+        let results = detect_links("[install](README.md#installation)", OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path.text, "README.md");
+        assert_eq!(results[0].suffix, None);
+    }
+
+    #[test]
+    pub fn test_markdown_image_and_angled_target() {
+        let results = detect_links(
+            r#"![logo](./img/logo.png "Logo") and [docs](<./my docs/a.md>)"#,
+            OperatingSystem::Linux,
+        );
+
+        let paths: Vec<&str> = results.iter().map(|l| l.path.text.as_str()).collect();
+        assert_eq!(paths, vec!["./img/logo.png", "./my docs/a.md"]);
+        assert_eq!(results[1].prefix.as_ref().unwrap().text, "[docs](<");
+    }
+
+    #[test]
+    pub fn test_autolink() {
+        let results = detect_links("Read <./foo.md> but not <div>", OperatingSystem::Linux);
+
+        let expected = vec![ParsedLink {
+            path: LinkPartialRange {
+                index: 6,
+                text: "./foo.md".to_string(),
+            },
+            prefix: Some(LinkPartialRange {
+                index: 5,
+                text: "<".to_string(),
+            }),
+            suffix: None,
+            confidence: 100,
+        }];
+
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    pub fn test_rst_link() {
+        let line = "See `the setup guide <docs/setup.rst#L3>`_ and `other <other.rst>`__";
+        let results = detect_links(line, OperatingSystem::Linux);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].path.text, "docs/setup.rst");
+        assert_eq!(
+            results[0].prefix.as_ref().unwrap().text,
+            "`the setup guide <"
+        );
+        assert_eq!(results[0].suffix.as_ref().unwrap().row, Some(3));
+        assert_eq!(results[1].path.text, "other.rst");
+    }
+
+    #[test]
+    pub fn test_markup_ignores_urls_and_anchors() {
+        let results = detect_links(
+            "[site](https://example.com/a/b) [top](#top) [mail](mailto:a@b.c)",
+            OperatingSystem::Linux,
+        );
+
+        assert!(
+            results
+                .iter()
+                .all(|l| l.prefix.as_ref().is_none_or(|p| !p.text.starts_with('['))),
+            "{:?}",
+            results
+        );
+    }
+
+    #[test]
+    pub fn test_markup_wins_over_generic_paths() {
+        let results = detect_links(
+            "[foo](src/foo.rs#L10) next to src/bar.rs:3",
+            OperatingSystem::Linux,
+        );
+
+        let paths: Vec<&str> = results.iter().map(|l| l.path.text.as_str()).collect();
+        assert_eq!(paths, vec!["src/foo.rs", "src/bar.rs"]);
+        assert_eq!(results[0].suffix.as_ref().unwrap().row, Some(10));
+    }
+}
@@ -0,0 +1,157 @@
+//! Motions between the links of a buffer, like `]l` and `[l`. Lines are only scanned up to the
+//! first one containing a link in the requested direction.
+
+use crate::{OperatingSystem, detect_links_with_min_confidence};
+use nvim_oxi::api;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Next,
+    Previous,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "next" => Ok(Self::Next),
+            "previous" => Ok(Self::Previous),
+            _ => Err(format!("unknown direction: {}", s)),
+        }
+    }
+}
+
+/// Returns the 1-based row and byte column of the start of the path of the next or previous link
+/// from the 1-based `row` and `col`. With `wrap` the search continues from the other end of the
+/// lines, up to and including the link at the position itself.
+pub fn find_adjacent_link<S: AsRef<str>>(
+    lines: &[S],
+    (row, col): (usize, usize),
+    direction: Direction,
+    wrap: bool,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> Option<(usize, usize)> {
+    let row = row.clamp(1, lines.len().max(1));
+    let columns = |row: usize| -> Vec<usize> {
+        lines.get(row - 1).map_or_else(Vec::new, |line| {
+            detect_links_with_min_confidence(line.as_ref(), os, min_confidence)
+                .iter()
+                .map(|link| link.path.index + 1)
+                .collect()
+        })
+    };
+    let first = |row: usize| columns(row).first().map(|col| (row, *col));
+    let last = |row: usize| columns(row).last().map(|col| (row, *col));
+    let current = columns(row);
+
+    match direction {
+        Direction::Next => current
+            .iter()
+            .find(|c| **c > col)
+            .map(|c| (row, *c))
+            .or_else(|| (row + 1..=lines.len()).find_map(first))
+            .or_else(|| {
+                wrap.then(|| {
+                    (1..row)
+                        .find_map(first)
+                        .or_else(|| current.first().filter(|c| **c <= col).map(|c| (row, *c)))
+                })
+                .flatten()
+            }),
+        Direction::Previous => current
+            .iter()
+            .rev()
+            .find(|c| **c < col)
+            .map(|c| (row, *c))
+            .or_else(|| (1..row).rev().find_map(last))
+            .or_else(|| {
+                wrap.then(|| {
+                    (row + 1..=lines.len())
+                        .rev()
+                        .find_map(last)
+                        .or_else(|| current.last().filter(|c| **c >= col).map(|c| (row, *c)))
+                })
+                .flatten()
+            }),
+    }
+}
+
+/// Finds the next or previous link from the cursor of the current window, in any kind of buffer
+/// including terminals.
+pub(crate) fn find_adjacent_link_from_cursor(
+    direction: Direction,
+    wrap: bool,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> nvim_oxi::Result<Option<(usize, usize)>> {
+    let (row, col) = api::get_current_win().get_cursor()?;
+    let lines: Vec<String> = api::get_current_buf()
+        .get_lines(.., false)?
+        .map(|line| line.to_string_lossy().into_owned())
+        .collect();
+
+    Ok(find_adjacent_link(
+        &lines,
+        (row, col + 1),
+        direction,
+        wrap,
+        os,
+        min_confidence,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::OperatingSystem;
+    use crate::navigation::{Direction, find_adjacent_link};
+    use pretty_assertions::assert_eq;
+
+    const LINES: [&str; 5] = [
+        "see a.rs:1 and b.rs:2",
+        "nothing here",
+        "",
+        "error --> src/main.rs:10:5",
+        "done",
+    ];
+
+    fn jump(position: (usize, usize), direction: Direction, wrap: bool) -> Option<(usize, usize)> {
+        find_adjacent_link(&LINES, position, direction, wrap, OperatingSystem::Linux, 0)
+    }
+
+    #[test]
+    pub fn test_next_link() {
+        assert_eq!(jump((1, 1), Direction::Next, false), Some((1, 5)));
+        assert_eq!(jump((1, 5), Direction::Next, false), Some((1, 16)));
+        assert_eq!(jump((1, 16), Direction::Next, false), Some((4, 11)));
+        assert_eq!(jump((4, 11), Direction::Next, false), None);
+        assert_eq!(jump((4, 11), Direction::Next, true), Some((1, 5)));
+    }
+
+    #[test]
+    pub fn test_previous_link() {
+        assert_eq!(jump((5, 1), Direction::Previous, false), Some((4, 11)));
+        assert_eq!(jump((4, 11), Direction::Previous, false), Some((1, 16)));
+        // Inside a link the motion goes to its start first
+        assert_eq!(jump((1, 18), Direction::Previous, false), Some((1, 16)));
+        assert_eq!(jump((1, 5), Direction::Previous, false), None);
+        assert_eq!(jump((1, 5), Direction::Previous, true), Some((4, 11)));
+    }
+
+    #[test]
+    pub fn test_wrap_returns_to_the_only_link() {
+        let lines = ["a.rs:1", "text"];
+
+        let next = find_adjacent_link(
+            &lines,
+            (1, 1),
+            Direction::Next,
+            true,
+            OperatingSystem::Linux,
+            0,
+        );
+        assert_eq!(next, Some((1, 1)));
+    }
+}
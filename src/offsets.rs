@@ -0,0 +1,59 @@
+//! Mapping of indices in a line that has been rewritten before detection, such as after replacing
+//! invalid UTF-8 or stripping escape sequences, back to indices in the original raw line.
+
+use crate::{LinkPartialRange, ParsedLink};
+
+/// The start of each segment of the rewritten line that was copied from the raw line. Indices
+/// within a segment keep their distance from the segment's start.
+#[derive(Debug, Default)]
+pub(crate) struct OffsetMap {
+    /// Triples of `(rewritten_index, raw_index, raw_length)` sorted by the rewritten index
+    segments: Vec<(usize, usize, usize)>,
+}
+
+impl OffsetMap {
+    /// Records that the text at `rewritten_index` in the rewritten line was `raw_length` bytes
+    /// starting at `raw_index` in the raw line. Segments must be pushed in order.
+    pub(crate) fn push(&mut self, rewritten_index: usize, raw_index: usize, raw_length: usize) {
+        self.segments.push((rewritten_index, raw_index, raw_length));
+    }
+
+    /// Converts an index in the rewritten line to the index in the raw line.
+    pub(crate) fn to_raw(&self, index: usize) -> usize {
+        let segment = self.segments.partition_point(|(start, ..)| *start <= index);
+        match segment.checked_sub(1).map(|i| self.segments[i]) {
+            Some((start, raw_start, _)) => raw_start + (index - start),
+            None => index,
+        }
+    }
+
+    /// Converts the exclusive end of a range in the rewritten line to the end in the raw line. The
+    /// end is mapped from the segment of the last byte of the range, so that it doesn't include
+    /// anything removed after the range, and a replacement that is longer than the text it replaced
+    /// ends where the replaced text ends.
+    pub(crate) fn to_raw_end(&self, end: usize) -> usize {
+        let Some(last) = end.checked_sub(1) else {
+            return self.to_raw(end);
+        };
+        let segment = self.segments.partition_point(|(start, ..)| *start <= last);
+        match segment.checked_sub(1).map(|i| self.segments[i]) {
+            Some((start, raw_start, raw_length)) => raw_start + (end - start).min(raw_length),
+            None => end,
+        }
+    }
+
+    /// Converts the indices of all parts of the link to indices in the raw line, the text of the
+    /// parts is kept as it was in the rewritten line.
+    pub(crate) fn map_link(&self, mut link: ParsedLink) -> ParsedLink {
+        let map_range = |range: &mut LinkPartialRange| range.index = self.to_raw(range.index);
+
+        map_range(&mut link.path);
+        if let Some(prefix) = link.prefix.as_mut() {
+            map_range(prefix);
+        }
+        if let Some(suffix) = link.suffix.as_mut() {
+            map_range(&mut suffix.suffix);
+        }
+        link
+    }
+}
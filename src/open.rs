@@ -0,0 +1,379 @@
+//! Opening links in a window chosen by an [`OpenPolicy`], so that opening a link from a terminal
+//! doesn't replace the terminal, and moving to or selecting the location of the link suffix.
+//! Directories are opened with `:edit`, which shows them in netrw or oil, while images, PDFs and
+//! other binary files are opened by the system.
+
+use crate::kind::{FileKind, classify_path};
+use crate::{
+    LinkLookup, LinkSuffix, OperatingSystem, ParsedLink, detect_links_with_min_confidence,
+    find_link_near_position, link_range, permalink, unescape_spaces,
+};
+use nvim_oxi::api::{self, Window};
+use nvim_oxi::{Array, Object};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// The window variable marking the split created by [`OpenPolicy::Split`]
+const SPLIT_VAR: &str = "fetch_split";
+
+/// The window to open a link in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenPolicy {
+    /// The current window, like `:edit`
+    Current,
+    /// A window of the current tab page already showing the file, or else the previous window
+    #[default]
+    Reuse,
+    /// The previous window, or else the dedicated split
+    Previous,
+    /// A split that is created for the first link and reused by the following ones
+    Split,
+}
+
+impl FromStr for OpenPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "current" => Ok(Self::Current),
+            "reuse" => Ok(Self::Reuse),
+            "previous" => Ok(Self::Previous),
+            "split" => Ok(Self::Split),
+            _ => Err(format!("unknown open policy: {}", s)),
+        }
+    }
+}
+
+/// The part of the file to show once it's open. Rows and columns are 1-based and the end column is
+/// inclusive, as in [`LinkSuffix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Selection {
+    pub row: Option<u32>,
+    pub col: Option<u32>,
+    pub row_end: Option<u32>,
+    pub col_end: Option<u32>,
+}
+
+impl From<&LinkSuffix> for Selection {
+    fn from(suffix: &LinkSuffix) -> Self {
+        Self {
+            row: suffix.row,
+            col: suffix.col,
+            row_end: suffix.row_end,
+            col_end: suffix.col_end,
+        }
+    }
+}
+
+/// The cursor positions for a [`Selection`], as 1-based rows and 0-based byte columns like
+/// `nvim_win_set_cursor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CursorRange {
+    start: (usize, usize),
+    /// The visual mode, `v` or `V`, and the other end of the selection
+    visual: Option<(char, (usize, usize))>,
+}
+
+/// Computes where to put the cursor for the selection in a buffer of `line_count` lines, rows past
+/// the end of the buffer are moved to its last line.
+fn cursor_range(selection: &Selection, line_count: usize) -> Option<CursorRange> {
+    let clamp_row = |row: u32| (row as usize).clamp(1, line_count.max(1));
+    let to_col = |col: u32| (col as usize).saturating_sub(1);
+
+    let row = clamp_row(selection.row?);
+    let start = (row, selection.col.map_or(0, to_col));
+    let row_end = selection.row_end.map(clamp_row);
+
+    let visual = match (row_end, selection.col_end) {
+        (row_end, Some(col_end)) => Some(('v', (row_end.unwrap_or(row), to_col(col_end)))),
+        (Some(row_end), None) if row_end != row => Some(('V', (row_end, 0))),
+        _ => None,
+    };
+    Some(CursorRange { start, visual })
+}
+
+/// Picks the window to open `file` in, creating the dedicated split when needed.
+fn target_window(file: &Path, policy: OpenPolicy) -> nvim_oxi::Result<Window> {
+    let current = api::get_current_win();
+
+    match policy {
+        OpenPolicy::Current => Ok(current),
+        OpenPolicy::Reuse => {
+            for window in api::get_current_tabpage().list_wins()? {
+                if window.get_buf()?.get_name()? == file {
+                    return Ok(window);
+                }
+            }
+            target_window(file, OpenPolicy::Previous)
+        }
+        OpenPolicy::Previous => {
+            let number: u32 = api::call_function("winnr", Array::from_iter([Object::from("#")]))?;
+            if number != 0 {
+                let handle: i32 = api::call_function("win_getid", Array::from_iter([number]))?;
+                let window = Window::from(handle);
+                let kind: String = api::call_function("win_gettype", Array::from_iter([handle]))?;
+                // Floating windows are usually pickers or previews that are about to close
+                if window != current && kind.is_empty() {
+                    return Ok(window);
+                }
+            }
+            target_window(file, OpenPolicy::Split)
+        }
+        OpenPolicy::Split => {
+            for window in api::get_current_tabpage().list_wins()? {
+                if window.get_var::<bool>(SPLIT_VAR).unwrap_or(false) {
+                    return Ok(window);
+                }
+            }
+            api::command("split")?;
+            let mut window = api::get_current_win();
+            window.set_var(SPLIT_VAR, true)?;
+            Ok(window)
+        }
+    }
+}
+
+/// Opens the file or directory with the default application of the system, ie. `xdg-open`, `open`
+/// or `explorer`, which opens directories in the file manager.
+pub fn open_with_system(path: &Path, os: OperatingSystem) -> std::io::Result<()> {
+    let program = match os {
+        OperatingSystem::Linux => "xdg-open",
+        OperatingSystem::Macintosh => "open",
+        OperatingSystem::Windows => "explorer",
+    };
+
+    let mut child = Command::new(program)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // The opener usually hands the file over to another process and exits right away
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Opens the file in the window picked by `policy` and moves the cursor to the selection, or
+/// selects it visually when it's a range. Binary files are opened by the system instead. Returns
+/// `false` if the file doesn't exist.
+pub(crate) fn open_location(
+    path: &str,
+    selection: &Selection,
+    policy: OpenPolicy,
+    os: OperatingSystem,
+) -> nvim_oxi::Result<bool> {
+    let file = full_path(path)?;
+    let file = file.as_path();
+    let Some(kind) = classify_path(file) else {
+        return Ok(false);
+    };
+
+    if kind == FileKind::Binary {
+        open_with_system(file, os).map_err(|e| api::Error::Other(e.to_string()))?;
+        return Ok(true);
+    }
+
+    let mut window = target_window(file, policy)?;
+    api::set_current_win(&window)?;
+    if window.get_buf()?.get_name()? != file {
+        let escaped: String = api::call_function(
+            "fnameescape",
+            Array::from_iter([Object::from(file.to_string_lossy().as_ref())]),
+        )?;
+        api::command(&format!("edit {}", escaped))?;
+    }
+
+    if kind == FileKind::Directory {
+        return Ok(true);
+    }
+
+    let line_count = window.get_buf()?.line_count()?;
+    if let Some(range) = cursor_range(selection, line_count) {
+        // Add the previous position to the jumplist
+        api::command("normal! m'")?;
+        window.set_cursor(range.start.0, range.start.1)?;
+        if let Some((mode, (row, col))) = range.visual {
+            api::command(&format!("normal! {}", mode))?;
+            window.set_cursor(row, col)?;
+        }
+        api::command("normal! zv")?;
+    }
+
+    Ok(true)
+}
+
+/// Expands `~` and makes the path absolute, relative to the current directory of the window.
+pub(crate) fn full_path(path: &str) -> nvim_oxi::Result<PathBuf> {
+    let path: String = api::call_function(
+        "fnamemodify",
+        Array::from_iter([Object::from(path), Object::from(":p")]),
+    )?;
+    Ok(PathBuf::from(path))
+}
+
+/// Returns the path and selection of the link under the cursor, or of one near it depending on
+/// `lookup`.
+pub(crate) fn link_under_cursor(
+    os: OperatingSystem,
+    min_confidence: u8,
+    lookup: LinkLookup,
+) -> nvim_oxi::Result<Option<(String, Selection)>> {
+    let line = api::get_current_line()?;
+    let (_, col) = api::get_current_win().get_cursor()?;
+    let links = detect_links_with_min_confidence(&line, os, min_confidence);
+    let local_link = |link: &ParsedLink| {
+        let path = unescape_spaces(&link.path.text, os);
+        let selection = link
+            .suffix
+            .as_ref()
+            .map(Selection::from)
+            .unwrap_or_default();
+        (path, selection)
+    };
+
+    if let Some(link) = find_link_near_position(&links, col + 1, LinkLookup::Exact) {
+        return Ok(Some(local_link(link)));
+    }
+
+    // Permalinks aren't local paths, they are opened in the local clone of their repository. A
+    // permalink under the cursor is preferred over local links near it
+    let permalink = permalink::detect_permalinks(&line)
+        .into_iter()
+        .find(|permalink| {
+            let (start, end) = link_range(&permalink.link);
+            (start..end).contains(&col)
+        });
+    if let Some(permalink) = permalink {
+        let cwd: String = api::call_function("getcwd", Array::new())?;
+        if let Some(path) = permalink.local_path_in_repository(Path::new(&cwd)) {
+            let selection = permalink
+                .link
+                .suffix
+                .as_ref()
+                .map(Selection::from)
+                .unwrap_or_default();
+            return Ok(Some((path.to_string_lossy().into_owned(), selection)));
+        }
+    }
+
+    Ok(find_link_near_position(&links, col + 1, lookup).map(local_link))
+}
+
+/// Opens the link under the cursor, see [`open_location`].
+pub(crate) fn open_link_under_cursor(
+    policy: OpenPolicy,
+    os: OperatingSystem,
+    min_confidence: u8,
+    lookup: LinkLookup,
+) -> nvim_oxi::Result<bool> {
+    match link_under_cursor(os, min_confidence, lookup)? {
+        Some((path, selection)) => open_location(&path, &selection, policy, os),
+        None => Ok(false),
+    }
+}
+
+/// Opens the file or directory of the link under the cursor with the system, see
+/// [`open_with_system`].
+pub(crate) fn open_link_under_cursor_with_system(
+    os: OperatingSystem,
+    min_confidence: u8,
+    lookup: LinkLookup,
+) -> nvim_oxi::Result<bool> {
+    let Some((path, _)) = link_under_cursor(os, min_confidence, lookup)? else {
+        return Ok(false);
+    };
+
+    let file = full_path(&path)?;
+    if !file.exists() {
+        return Ok(false);
+    }
+    open_with_system(&file, os).map_err(|e| api::Error::Other(e.to_string()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::open::{CursorRange, Selection, cursor_range};
+    use pretty_assertions::assert_eq;
+
+    fn selection(
+        row: u32,
+        col: Option<u32>,
+        row_end: Option<u32>,
+        col_end: Option<u32>,
+    ) -> Selection {
+        Selection {
+            row: Some(row),
+            col,
+            row_end,
+            col_end,
+        }
+    }
+
+    #[test]
+    pub fn test_cursor_range() {
+        let cases = [
+            (Selection::default(), None),
+            (
+                selection(11, None, None, None),
+                Some(CursorRange {
+                    start: (11, 0),
+                    visual: None,
+                }),
+            ),
+            (
+                selection(11, Some(111), None, None),
+                Some(CursorRange {
+                    start: (11, 110),
+                    visual: None,
+                }),
+            ),
+            (
+                selection(11, Some(111), None, Some(222)),
+                Some(CursorRange {
+                    start: (11, 110),
+                    visual: Some(('v', (11, 221))),
+                }),
+            ),
+            (
+                selection(11, Some(111), Some(22), Some(222)),
+                Some(CursorRange {
+                    start: (11, 110),
+                    visual: Some(('v', (22, 221))),
+                }),
+            ),
+            (
+                selection(11, None, Some(22), None),
+                Some(CursorRange {
+                    start: (11, 0),
+                    visual: Some(('V', (22, 0))),
+                }),
+            ),
+            (
+                selection(11, None, Some(11), None),
+                Some(CursorRange {
+                    start: (11, 0),
+                    visual: None,
+                }),
+            ),
+        ];
+
+        for (selection, expected) in cases {
+            assert_eq!(cursor_range(&selection, 100), expected, "{:?}", selection);
+        }
+    }
+
+    #[test]
+    pub fn test_cursor_range_is_clamped_to_the_buffer() {
+        let range = cursor_range(&selection(150, Some(3), Some(200), None), 100);
+
+        assert_eq!(
+            range,
+            Some(CursorRange {
+                start: (100, 2),
+                visual: None,
+            })
+        );
+    }
+}
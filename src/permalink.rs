@@ -0,0 +1,560 @@
+//! Parsing of permalinks to files hosted on a forge, such as
+//! `https://github.com/org/repo/blob/<sha>/src/lib.rs#L10-L20`. The repository relative path, the
+//! ref and the line range are extracted and can optionally be mapped to a local clone of the
+//! repository.
+//!
+//! Refs are assumed to be a single path segment, ie. branch names containing `/` will be split
+//! between the ref and the path.
+
+use crate::format::Location;
+use crate::{LinkPartialRange, LinkSuffix, ParsedLink, markup};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, lua};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Gitea,
+    Bitbucket,
+    Sourcehut,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Permalink {
+    pub forge: Forge,
+    pub host: String,
+    /// The repository including its owner, eg. `org/repo` or `~user/repo`.
+    pub repo: String,
+    pub git_ref: String,
+    /// The link to the file, the path is relative to the root of the repository and everything in
+    /// the url before the path is the prefix.
+    pub link: ParsedLink,
+}
+
+impl ToObject for Permalink {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for Permalink {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// A regex that matches http(s) urls.
+static URL_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"https?://[^\s/<>"'`]+/[^\s<>"'`\)\]]+"#).unwrap());
+
+/// A regex that matches Bitbucket's line anchors, such as `#lines-10` and `#lines-10:20`.
+static BITBUCKET_FRAGMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#lines-(?P<row>\d+)(?::(?P<rowEnd>\d+))?$").unwrap());
+
+/// Detects all forge permalinks on the line.
+pub fn detect_permalinks(line: &str) -> Vec<Permalink> {
+    URL_REGEX
+        .find_iter(line)
+        .filter_map(|m| {
+            // Don't include punctuation ending a sentence in the url
+            let url = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            parse_permalink_at(url, m.start())
+        })
+        .collect()
+}
+
+/// Parses a single forge permalink, the indexes of the returned link are relative to the url.
+pub fn parse_permalink(url: &str) -> Option<Permalink> {
+    parse_permalink_at(url, 0)
+}
+
+fn parse_permalink_at(url: &str, index: usize) -> Option<Permalink> {
+    let host_start = url.find("://")? + 3;
+    let host_end = host_start + url[host_start..].find('/')?;
+    let host = &url[host_start..host_end];
+
+    let fragment_start = url.find('#').unwrap_or(url.len());
+    let path_end = url[..fragment_start].find('?').unwrap_or(fragment_start);
+
+    // Split the url path into its segments, keeping track of where each one starts
+    let mut segments: Vec<(usize, &str)> = Vec::new();
+    let mut offset = host_end + 1;
+    for segment in url[host_end + 1..path_end].split('/') {
+        segments.push((offset, segment));
+        offset += segment.len() + 1;
+    }
+    let segment = |i: usize| segments.get(i).map_or("", |(_, s)| *s);
+
+    let (forge, repo_segments, ref_segment, path_segment) = if let Some(dash) =
+        segments.iter().position(|(_, s)| *s == "-")
+        && matches!(segment(dash + 1), "blob" | "blame" | "raw")
+    {
+        (Forge::GitLab, dash, dash + 2, dash + 3)
+    } else if segment(0).starts_with('~') && segment(2) == "tree" && segment(4) == "item" {
+        (Forge::Sourcehut, 2, 3, 5)
+    } else if segment(2) == "src" && matches!(segment(3), "commit" | "branch" | "tag") {
+        (Forge::Gitea, 2, 4, 5)
+    } else if host.contains("bitbucket") && segment(2) == "src" {
+        (Forge::Bitbucket, 2, 3, 4)
+    } else if matches!(segment(2), "blob" | "blame") {
+        (Forge::GitHub, 2, 3, 4)
+    } else if host == "raw.githubusercontent.com" {
+        (Forge::GitHub, 2, 2, 3)
+    } else {
+        return None;
+    };
+
+    let git_ref = segment(ref_segment);
+    let &(path_start, _) = segments.get(path_segment)?;
+    let path = &url[path_start..path_end];
+    if repo_segments < 2 || git_ref.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    let repo = segments[..repo_segments]
+        .iter()
+        .map(|(_, s)| *s)
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let suffix = (fragment_start < url.len())
+        .then(|| {
+            let fragment = &url[fragment_start..];
+            match forge {
+                Forge::Bitbucket => bitbucket_link_suffix(fragment, index + fragment_start),
+                _ => markup::link_suffix_from_fragment(fragment, index + fragment_start),
+            }
+        })
+        .flatten();
+
+    Some(Permalink {
+        forge,
+        host: host.to_string(),
+        repo,
+        git_ref: git_ref.to_string(),
+        link: ParsedLink {
+            path: LinkPartialRange {
+                index: index + path_start,
+                text: path.to_string(),
+            },
+            prefix: Some(LinkPartialRange {
+                index,
+                text: url[..path_start].to_string(),
+            }),
+            suffix,
+            confidence: 100,
+        },
+    })
+}
+
+fn bitbucket_link_suffix(fragment: &str, index: usize) -> Option<LinkSuffix> {
+    let captures = BITBUCKET_FRAGMENT_REGEX.captures(fragment)?;
+    let parse_int_opt = |name: &str| -> Option<u32> {
+        captures
+            .name(name)
+            .and_then(|m| m.as_str().parse::<u32>().ok())
+    };
+
+    Some(LinkSuffix {
+        row: parse_int_opt("row"),
+        col: None,
+        row_end: parse_int_opt("rowEnd"),
+        col_end: None,
+        suffix: LinkPartialRange {
+            index,
+            text: fragment.to_string(),
+        },
+    })
+}
+
+impl Permalink {
+    /// Whether the permalink points into the repository with the given git remote url, such as
+    /// `git@github.com:org/repo.git` or `https://github.com/org/repo`.
+    pub fn matches_remote(&self, remote_url: &str) -> bool {
+        normalize_remote_url(remote_url).is_some_and(|(host, repo)| {
+            host.eq_ignore_ascii_case(&self.host) && repo.eq_ignore_ascii_case(&self.repo)
+        })
+    }
+
+    /// Maps the permalink to a file in a local clone of the repository if any of its remotes match.
+    pub fn local_path<S: AsRef<str>>(
+        &self,
+        repo_root: &Path,
+        remote_urls: &[S],
+    ) -> Option<PathBuf> {
+        remote_urls
+            .iter()
+            .any(|url| self.matches_remote(url.as_ref()))
+            .then(|| repo_root.join(percent_decode(&self.link.path.text)))
+    }
+
+    /// Maps the permalink to a file in the git repository containing `dir` if any of its remotes
+    /// match.
+    pub fn local_path_in_repository(&self, dir: &Path) -> Option<PathBuf> {
+        let repo_root = git(dir, &["rev-parse", "--show-toplevel"])?;
+        let remotes = git(dir, &["config", "--get-regexp", r"^remote\..*\.url$"])?;
+        let remote_urls: Vec<&str> = remotes
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .collect();
+
+        self.local_path(Path::new(repo_root.trim()), &remote_urls)
+    }
+}
+
+/// Runs git in `dir`, returning its output if it succeeded.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A repository hosted on a forge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    pub forge: Forge,
+    pub host: String,
+    /// The repository including its owner, eg. `org/repo` or `~user/repo`.
+    pub repo: String,
+}
+
+impl Repository {
+    /// Creates a repository from a git remote url, such as `git@github.com:org/repo.git`. The forge
+    /// is guessed from the host so `None` is returned for unknown self-hosted forges.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let (host, repo) = normalize_remote_url(url)?;
+        let forge = match host.to_ascii_lowercase() {
+            h if h.contains("github") => Forge::GitHub,
+            h if h.contains("gitlab") => Forge::GitLab,
+            h if h.contains("bitbucket") => Forge::Bitbucket,
+            h if h.contains("sr.ht") => Forge::Sourcehut,
+            h if ["gitea", "forgejo", "codeberg"]
+                .iter()
+                .any(|f| h.contains(f)) =>
+            {
+                Forge::Gitea
+            }
+            _ => return None,
+        };
+
+        Some(Self { forge, host, repo })
+    }
+}
+
+/// Formats a permalink to the location in the repository, this is the inverse of
+/// [`parse_permalink`]. The location's path must be relative to the root of the repository.
+/// Columns are only supported by GitHub and are dropped for other forges.
+pub fn format_permalink(repository: &Repository, git_ref: &str, location: &Location) -> String {
+    let Repository { forge, host, repo } = repository;
+    let path = percent_encode(&location.path);
+    let (row, row_end) = (location.row, location.row_end);
+
+    match forge {
+        Forge::GitHub => {
+            let mut fragment = format!("#L{}", row);
+            if let Some(col) = location.col {
+                fragment += &format!("C{}", col);
+            }
+            if row_end.is_some() || location.col_end.is_some() {
+                fragment += &format!("-L{}", row_end.unwrap_or(row));
+                if let Some(col_end) = location.col_end.filter(|_| location.col.is_some()) {
+                    fragment += &format!("C{}", col_end);
+                }
+            }
+            format!(
+                "https://{}/{}/blob/{}/{}{}",
+                host, repo, git_ref, path, fragment
+            )
+        }
+        Forge::GitLab => format!(
+            "https://{}/{}/-/blob/{}/{}#L{}{}",
+            host,
+            repo,
+            git_ref,
+            path,
+            row,
+            row_end.map_or(String::new(), |r| format!("-{}", r))
+        ),
+        Forge::Gitea => format!(
+            "https://{}/{}/src/commit/{}/{}#L{}{}",
+            host,
+            repo,
+            git_ref,
+            path,
+            row,
+            row_end.map_or(String::new(), |r| format!("-L{}", r))
+        ),
+        Forge::Bitbucket => format!(
+            "https://{}/{}/src/{}/{}#lines-{}{}",
+            host,
+            repo,
+            git_ref,
+            path,
+            row,
+            row_end.map_or(String::new(), |r| format!(":{}", r))
+        ),
+        Forge::Sourcehut => format!(
+            "https://{}/{}/tree/{}/item/{}#L{}{}",
+            host,
+            repo,
+            git_ref,
+            path,
+            row,
+            row_end.map_or(String::new(), |r| format!("-{}", r))
+        ),
+    }
+}
+
+/// Splits a git remote url into its host and repository, eg. `git@github.com:org/repo.git` becomes
+/// `("github.com", "org/repo")`.
+fn normalize_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    let (host, repo) = if let Some(scheme_end) = url.find("://") {
+        // https://github.com/org/repo or ssh://git@github.com:22/org/repo
+        let rest = &url[scheme_end + 3..];
+        let (authority, repo) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        (host.split(':').next()?, repo)
+    } else {
+        // git@github.com:org/repo
+        let (authority, repo) = url.split_once(':')?;
+        (authority.rsplit('@').next()?, repo)
+    };
+
+    (!host.is_empty() && !repo.is_empty()).then(|| (host.to_string(), repo.to_string()))
+}
+
+/// Percent encodes the characters of a path that would otherwise end the url path.
+fn percent_encode(path: &str) -> String {
+    path.chars()
+        .map(|c| match c {
+            '%' => "%25".to_string(),
+            ' ' => "%20".to_string(),
+            '#' => "%23".to_string(),
+            '?' => "%3F".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Decodes the percent encoded characters of a url path, eg. `%20` becomes a space.
+pub(crate) fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = path
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::format::Location;
+    use crate::permalink::{
+        Forge, Repository, detect_permalinks, format_permalink, parse_permalink,
+    };
+    use crate::{LinkPartialRange, LinkSuffix, OperatingSystem, ParsedLink, detect_links};
+    use pretty_assertions::assert_eq;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn test_github_permalink() {
+        let line = "see https://github.com/org/repo/blob/abc123/src/lib.rs#L10-L20.";
+        let results = detect_permalinks(line);
+
+        assert_eq!(results.len(), 1);
+        let permalink = &results[0];
+        assert_eq!(permalink.forge, Forge::GitHub);
+        assert_eq!(permalink.host, "github.com");
+        assert_eq!(permalink.repo, "org/repo");
+        assert_eq!(permalink.git_ref, "abc123");
+        assert_eq!(
+            permalink.link,
+            ParsedLink {
+                path: LinkPartialRange {
+                    index: 44,
+                    text: "src/lib.rs".to_string(),
+                },
+                prefix: Some(LinkPartialRange {
+                    index: 4,
+                    text: "https://github.com/org/repo/blob/abc123/".to_string(),
+                }),
+                suffix: Some(LinkSuffix {
+                    row: Some(10),
+                    col: None,
+                    row_end: Some(20),
+                    col_end: None,
+                    suffix: LinkPartialRange {
+                        index: 54,
+                        text: "#L10-L20".to_string(),
+                    },
+                }),
+                confidence: 100,
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_forge_permalinks() {
+        let cases = [
+            (
+                "https://gitlab.com/group/sub/repo/-/blob/main/src/lib.rs?ref_type=heads#L10-20",
+                Forge::GitLab,
+                "group/sub/repo",
+                "main",
+                Some(20),
+            ),
+            (
+                "https://codeberg.org/org/repo/src/commit/abc123/src/lib.rs#L10-L20",
+                Forge::Gitea,
+                "org/repo",
+                "abc123",
+                Some(20),
+            ),
+            (
+                "https://bitbucket.org/org/repo/src/abc123/src/lib.rs#lines-10:20",
+                Forge::Bitbucket,
+                "org/repo",
+                "abc123",
+                Some(20),
+            ),
+            (
+                "https://git.sr.ht/~user/repo/tree/abc123/item/src/lib.rs#L10",
+                Forge::Sourcehut,
+                "~user/repo",
+                "abc123",
+                None,
+            ),
+        ];
+
+        for (url, forge, repo, git_ref, row_end) in cases {
+            let permalink = parse_permalink(url).expect(url);
+            assert_eq!(permalink.forge, forge, "{}", url);
+            assert_eq!(permalink.repo, repo, "{}", url);
+            assert_eq!(permalink.git_ref, git_ref, "{}", url);
+            assert_eq!(permalink.link.path.text, "src/lib.rs", "{}", url);
+
+            let suffix = permalink.link.suffix.expect(url);
+            assert_eq!((suffix.row, suffix.row_end), (Some(10), row_end), "{}", url);
+        }
+    }
+
+    #[test]
+    pub fn test_non_permalink_urls() {
+        for url in [
+            "https://github.com/org/repo",
+            "https://github.com/org/repo/issues/10",
+            "https://example.com/a/b/c",
+        ] {
+            assert_eq!(parse_permalink(url), None, "{}", url);
+        }
+    }
+
+    #[test]
+    pub fn test_permalinks_not_in_detect_links() {
+        let line = "at https://github.com/org/repo/blob/main/my%20docs/lib.rs#L3 and ./foo/bar";
+        let results = detect_links(line, OperatingSystem::Linux);
+
+        // The repository relative path isn't a local path and nothing inside the url is detected
+        let paths: Vec<&str> = results.iter().map(|l| l.path.text.as_str()).collect();
+        assert_eq!(paths, vec!["./foo/bar"]);
+        assert_eq!(detect_permalinks(line).len(), 1);
+    }
+
+    #[test]
+    pub fn test_permalink_local_path() {
+        let permalink =
+            parse_permalink("https://github.com/Org/Repo/blob/main/my%20docs/a.md#L3").unwrap();
+        let root = Path::new("/src/repo");
+
+        for remote in [
+            "git@github.com:org/repo.git",
+            "https://github.com/org/repo",
+            "ssh://git@github.com:22/org/repo.git",
+        ] {
+            assert_eq!(
+                permalink.local_path(root, &[remote]),
+                Some(PathBuf::from("/src/repo/my docs/a.md")),
+                "{}",
+                remote
+            );
+        }
+
+        assert_eq!(
+            permalink.local_path(root, &["git@gitlab.com:org/repo.git"]),
+            None
+        );
+    }
+
+    #[test]
+    pub fn test_format_permalink_round_trip() {
+        let location = Location {
+            path: "my docs/lib.rs".to_string(),
+            row: 10,
+            col: Some(5),
+            row_end: Some(20),
+            col_end: Some(8),
+        };
+
+        for (remote, forge) in [
+            ("git@github.com:org/repo.git", Forge::GitHub),
+            ("https://gitlab.com/org/repo.git", Forge::GitLab),
+            ("https://codeberg.org/org/repo", Forge::Gitea),
+            ("git@bitbucket.org:org/repo.git", Forge::Bitbucket),
+            ("https://git.sr.ht/~org/repo", Forge::Sourcehut),
+        ] {
+            let repository = Repository::from_remote_url(remote).expect(remote);
+            assert_eq!(repository.forge, forge);
+
+            let url = format_permalink(&repository, "abc123", &location);
+            let permalink = parse_permalink(&url).expect(&url);
+            assert_eq!(permalink.forge, forge, "{}", url);
+            assert_eq!(permalink.host, repository.host, "{}", url);
+            assert_eq!(permalink.repo, repository.repo, "{}", url);
+            assert_eq!(permalink.git_ref, "abc123", "{}", url);
+            assert_eq!(permalink.link.path.text, "my%20docs/lib.rs", "{}", url);
+
+            let suffix = permalink.link.suffix.expect(&url);
+            assert_eq!(
+                (suffix.row, suffix.row_end),
+                (Some(10), Some(20)),
+                "{}",
+                url
+            );
+            if forge == Forge::GitHub {
+                assert_eq!((suffix.col, suffix.col_end), (Some(5), Some(8)), "{}", url);
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Entries for pickers such as `vim.ui.select`, telescope or fzf listing the links of one or all
+//! listed buffers. Links to the same line of the same file are only listed once.
+
+use crate::format::{Location, LocationFormat, format_location};
+use crate::{OperatingSystem, detect_links_with_min_confidence, unescape_spaces};
+use nvim_oxi::api;
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Array, Object, lua};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PickerEntry {
+    /// The location as written in the buffer followed by the line it was found on, eg.
+    /// `src/main.rs:10:5: error --> src/main.rs:10:5`
+    pub display: String,
+    /// The path resolved against the working directory
+    pub path: String,
+    pub row: Option<u32>,
+    pub col: Option<u32>,
+    /// The buffer and 1-based line containing the link
+    pub bufnr: i32,
+    pub line: usize,
+}
+
+impl ToObject for PickerEntry {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for PickerEntry {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// Collects the links of the lines of each buffer, in order, keeping only the first link to each
+/// resolved path and row.
+pub fn picker_entries<S: AsRef<str>>(
+    buffers: &[(i32, Vec<S>)],
+    cwd: &Path,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> Vec<PickerEntry> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+
+    for (bufnr, lines) in buffers {
+        for (index, line) in lines.iter().enumerate() {
+            let line = line.as_ref();
+            for link in detect_links_with_min_confidence(line, os, min_confidence) {
+                let path = resolve_path(&link.path.text, cwd, os);
+                let row = link.suffix.as_ref().and_then(|suffix| suffix.row);
+                if !seen.insert((path.clone(), row)) {
+                    continue;
+                }
+
+                let col = link.suffix.as_ref().and_then(|suffix| suffix.col);
+                let location = match row {
+                    Some(row) => format_location(
+                        &Location {
+                            path: link.path.text.clone(),
+                            row,
+                            col,
+                            row_end: None,
+                            col_end: None,
+                        },
+                        LocationFormat::Colon,
+                    ),
+                    None => link.path.text.clone(),
+                };
+
+                entries.push(PickerEntry {
+                    display: format!("{}: {}", location, line.trim()),
+                    path: path.to_string_lossy().into_owned(),
+                    row,
+                    col,
+                    bufnr: *bufnr,
+                    line: index + 1,
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+/// Resolves the path of a link against `cwd`, expanding `~` and removing escapes from spaces outside
+/// of Windows as well as `.` and `..` components without touching the file system.
+fn resolve_path(text: &str, cwd: &Path, os: OperatingSystem) -> PathBuf {
+    let text = unescape_spaces(text, os);
+    let path = match text.strip_prefix("~/") {
+        Some(rest) => std::env::var_os("HOME")
+            .map_or_else(|| PathBuf::from(&text), |home| Path::new(&home).join(rest)),
+        None => cwd.join(&text),
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// Collects the picker entries of the current buffer, or of all listed buffers when `all_buffers`
+/// is set.
+pub(crate) fn picker_entries_from_buffers(
+    all_buffers: bool,
+    os: OperatingSystem,
+    min_confidence: u8,
+) -> nvim_oxi::Result<Vec<PickerEntry>> {
+    let buffers = if all_buffers {
+        let mut buffers = Vec::new();
+        for buffer in api::list_bufs() {
+            let listed: i64 = api::call_function("buflisted", Array::from_iter([buffer.handle()]))?;
+            if listed == 1 && buffer.is_loaded() {
+                buffers.push(buffer);
+            }
+        }
+        buffers
+    } else {
+        vec![api::get_current_buf()]
+    };
+
+    let mut lines = Vec::with_capacity(buffers.len());
+    for buffer in buffers {
+        let buffer_lines: Vec<String> = buffer
+            .get_lines(.., false)?
+            .map(|line| line.to_string_lossy().into_owned())
+            .collect();
+        lines.push((buffer.handle(), buffer_lines));
+    }
+
+    let cwd: String = api::call_function("getcwd", Array::new())?;
+    Ok(picker_entries(&lines, Path::new(&cwd), os, min_confidence))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::OperatingSystem;
+    use crate::picker::{PickerEntry, picker_entries, resolve_path};
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    #[test]
+    pub fn test_picker_entries() {
+        let buffers = [
+            (
+                1,
+                vec![
+                    "error --> src/main.rs:10:5",
+                    "see ./src/main.rs:10 and ./README.md",
+                ],
+            ),
+            (2, vec!["", "src/../src/main.rs:11"]),
+        ];
+
+        let entries = picker_entries(&buffers, Path::new("/repo"), OperatingSystem::Linux, 0);
+
+        assert_eq!(
+            entries,
+            vec![
+                PickerEntry {
+                    display: "src/main.rs:10:5: error --> src/main.rs:10:5".to_string(),
+                    path: "/repo/src/main.rs".to_string(),
+                    row: Some(10),
+                    col: Some(5),
+                    bufnr: 1,
+                    line: 1,
+                },
+                PickerEntry {
+                    display: "./README.md: see ./src/main.rs:10 and ./README.md".to_string(),
+                    path: "/repo/README.md".to_string(),
+                    row: None,
+                    col: None,
+                    bufnr: 1,
+                    line: 2,
+                },
+                PickerEntry {
+                    display: "src/../src/main.rs:11: src/../src/main.rs:11".to_string(),
+                    path: "/repo/src/main.rs".to_string(),
+                    row: Some(11),
+                    col: None,
+                    bufnr: 2,
+                    line: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_resolve_escaped_spaces() {
+        let cwd = Path::new("/repo");
+
+        assert_eq!(
+            resolve_path("My\\ Documents/a.rs", cwd, OperatingSystem::Linux),
+            Path::new("/repo/My Documents/a.rs")
+        );
+        assert_eq!(
+            resolve_path("My\\ Documents/a.rs", cwd, OperatingSystem::Windows),
+            Path::new("/repo/My\\ Documents/a.rs")
+        );
+    }
+}
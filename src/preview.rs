@@ -0,0 +1,207 @@
+//! Previews of the lines around the target of a link, read straight from the file so that
+//! previewing doesn't load a buffer.
+
+use crate::kind::is_binary;
+use crate::open::{self, Selection};
+use crate::{LinkLookup, OperatingSystem};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, api, lua};
+use serde::Serialize;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Files larger than this aren't previewed
+pub const MAX_PREVIEW_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Preview {
+    pub path: String,
+    pub lines: Vec<String>,
+    /// The 1-based row of the first line of the preview in the file
+    pub first_row: usize,
+    /// The 1-based rows of the target of the link, they are within the preview unless the target
+    /// is past the end of the file
+    pub row: Option<usize>,
+    pub row_end: Option<usize>,
+}
+
+impl ToObject for Preview {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for Preview {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// Returns up to `height` lines of `text` around the rows of the selection, with the range
+/// centered when it fits and starting at its first row otherwise. Without a row the preview starts
+/// at the top of the file.
+pub fn slice_lines(text: &str, selection: &Selection, height: usize) -> (usize, Vec<String>) {
+    let line_count = text.lines().count();
+    let first_row = match selection.row {
+        Some(row) => {
+            let row = (row as usize).clamp(1, line_count.max(1));
+            let row_end = selection.row_end.map_or(row, |end| (end as usize).max(row));
+            let margin = height.saturating_sub(row_end - row + 1) / 2;
+            let first_row = row.saturating_sub(margin).max(1);
+            // Show more lines before the range near the end of the file
+            first_row.min(line_count.saturating_sub(height) + 1)
+        }
+        None => 1,
+    };
+
+    let lines = text
+        .lines()
+        .skip(first_row - 1)
+        .take(height)
+        .map(str::to_string)
+        .collect();
+    (first_row, lines)
+}
+
+/// Reads the preview of the selection in the file. Fails for files larger than `max_size` bytes,
+/// such as [`MAX_PREVIEW_FILE_SIZE`], and binary files.
+pub fn read_preview(
+    path: &Path,
+    selection: &Selection,
+    height: usize,
+    max_size: u64,
+) -> Result<Preview, String> {
+    let error = |e: std::io::Error| format!("{}: {}", path.display(), e);
+
+    let size = fs::metadata(path).map_err(error)?.len();
+    if size > max_size {
+        return Err(format!("{}: too large to preview", path.display()));
+    }
+
+    let mut bytes = Vec::with_capacity(size as usize);
+    fs::File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(error)?;
+    if is_binary(&bytes) {
+        return Err(format!("{}: binary file", path.display()));
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let (first_row, lines) = slice_lines(&text, selection, height);
+    Ok(Preview {
+        path: path.to_string_lossy().into_owned(),
+        lines,
+        first_row,
+        row: selection.row.map(|row| row as usize),
+        row_end: selection
+            .row_end
+            .or(selection.row)
+            .map(|row_end| row_end as usize),
+    })
+}
+
+/// Reads the preview of the link under the cursor, or of one near it depending on `lookup`. `None`
+/// if there's no link or its file doesn't exist.
+pub(crate) fn preview_link_under_cursor(
+    height: usize,
+    os: OperatingSystem,
+    min_confidence: u8,
+    lookup: LinkLookup,
+) -> nvim_oxi::Result<Option<Preview>> {
+    let Some((path, selection)) = open::link_under_cursor(os, min_confidence, lookup)? else {
+        return Ok(None);
+    };
+
+    let file = open::full_path(&path)?;
+    if !file.is_file() {
+        return Ok(None);
+    }
+
+    read_preview(&file, &selection, height, MAX_PREVIEW_FILE_SIZE)
+        .map(Some)
+        .map_err(|e| api::Error::Other(e).into())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::open::Selection;
+    use crate::preview::{MAX_PREVIEW_FILE_SIZE, read_preview, slice_lines};
+    use crate::test_util::TempDir;
+    use pretty_assertions::assert_eq;
+
+    fn rows(selection: Selection, height: usize) -> (usize, Vec<String>) {
+        let text: String = (1..=100).map(|row| format!("{}\n", row)).collect();
+        slice_lines(&text, &selection, height)
+    }
+
+    fn selection(row: u32, row_end: Option<u32>) -> Selection {
+        Selection {
+            row: Some(row),
+            row_end,
+            ..Selection::default()
+        }
+    }
+
+    #[test]
+    pub fn test_slice_lines() {
+        let cases = [
+            (Selection::default(), 1, "1", "5"),
+            (selection(50, None), 48, "48", "52"),
+            (selection(50, Some(52)), 49, "49", "53"),
+            (selection(50, Some(60)), 50, "50", "54"),
+            (selection(2, None), 1, "1", "5"),
+            (selection(99, None), 96, "96", "100"),
+            (selection(500, None), 96, "96", "100"),
+        ];
+
+        for (selection, first_row, first, last) in cases {
+            let (row, lines) = rows(selection, 5);
+            assert_eq!(
+                (
+                    row,
+                    lines.first().unwrap().as_str(),
+                    lines.last().unwrap().as_str()
+                ),
+                (first_row, first, last),
+                "{:?}",
+                selection
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_slice_short_file() {
+        let (first_row, lines) = slice_lines("a\nb\n", &selection(2, None), 20);
+
+        assert_eq!(
+            (first_row, lines),
+            (1, vec!["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[test]
+    pub fn test_read_preview_rejects_binary_and_large_files() {
+        let dir = TempDir::new("preview");
+
+        let binary = dir.join("image.png");
+        std::fs::write(&binary, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        assert!(read_preview(&binary, &Selection::default(), 20, MAX_PREVIEW_FILE_SIZE).is_err());
+
+        let large = dir.join("large.txt");
+        std::fs::write(&large, vec![b'a'; 101]).unwrap();
+        assert!(read_preview(&large, &Selection::default(), 20, 100).is_err());
+        assert!(read_preview(&large, &Selection::default(), 20, 101).is_ok());
+
+        let text = dir.join("main.rs");
+        std::fs::write(&text, "fn main() {\n}\n").unwrap();
+        let preview = read_preview(&text, &selection(2, None), 20, MAX_PREVIEW_FILE_SIZE).unwrap();
+        assert_eq!(preview.lines, vec!["fn main() {", "}"]);
+        assert_eq!((preview.row, preview.row_end), (Some(2), Some(2)));
+    }
+}
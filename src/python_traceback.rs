@@ -0,0 +1,378 @@
+//! Parsing of whole Python tracebacks, including chained exceptions and the source and caret lines
+//! below each frame, instead of the single `File "foo.py", line 11` lines found by
+//! [`crate::detect_links`].
+
+use crate::{LinkSuffix, get_link_suffix};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, lua};
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+const CONTEXT_HEADER: &str = "During handling of the above exception, another exception occurred:";
+const CAUSE_HEADER: &str = "The above exception was the direct cause of the following exception:";
+
+/// How a traceback is chained to the one printed before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Chain {
+    /// The exception was raised while handling the previous one
+    Context,
+    /// The exception was raised `from` the previous one
+    Cause,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PythonFrame {
+    pub path: String,
+    /// The row of the frame, along with the columns of the carets in the file when its line can be
+    /// read, see [`parse_python_traceback`]
+    pub suffix: LinkSuffix,
+    /// The function of the frame, `<module>` for the top level of a module and `None` for the
+    /// location of a `SyntaxError`
+    pub function: Option<String>,
+    /// The source line printed below the frame, without its indentation
+    pub source: Option<String>,
+    /// The 1-based, inclusive columns of `source` marked by the `^` and `~` carets below it
+    pub caret: Option<(u32, u32)>,
+    /// The 1-based index of the line of the frame in the parsed lines
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PythonTraceback {
+    pub frames: Vec<PythonFrame>,
+    /// The line naming the exception, eg. `ZeroDivisionError: division by zero`
+    pub exception: Option<String>,
+    pub chain: Option<Chain>,
+}
+
+impl ToObject for PythonTraceback {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for PythonTraceback {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// Parses a `  File "foo.py", line 11, in bar` line into a frame.
+fn parse_frame(line: &str, index: usize) -> Option<PythonFrame> {
+    let location = line.trim().strip_prefix("File ")?;
+    let (location, function) = match location.rsplit_once(", in ") {
+        Some((location, function)) => (location, Some(function.to_string())),
+        None => (location, None),
+    };
+
+    let suffix = get_link_suffix(location)?;
+    suffix.row?;
+    let path = location[..suffix.suffix.index].trim_matches('"');
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(PythonFrame {
+        path: path.to_string(),
+        suffix,
+        function,
+        source: None,
+        caret: None,
+        line: index + 1,
+    })
+}
+
+/// Returns the columns of the carets relative to the source line, which is printed with the same
+/// indentation.
+fn parse_caret(line: &str, source_line: &str) -> Option<(u32, u32)> {
+    let carets = line.trim();
+    if carets.is_empty()
+        || !carets.contains('^')
+        || !carets.chars().all(|c| matches!(c, '^' | '~' | ' '))
+    {
+        return None;
+    }
+
+    let indent = source_line.len() - source_line.trim_start().len();
+    let start = (line.len() - line.trim_start().len()).checked_sub(indent)?;
+    Some((start as u32 + 1, (start + carets.len()) as u32))
+}
+
+/// Returns the columns of the carets in the file of the frame, whose relative path is resolved
+/// against `base_dir`. Tracebacks print the source line without its indentation, so it's read back
+/// from the file, and the columns are unknown when the file can't be read or its line no longer
+/// matches the source.
+fn file_columns(frame: &PythonFrame, base_dir: &Path) -> Option<(u32, u32)> {
+    let (start, end) = frame.caret?;
+    let source = frame.source.as_deref()?;
+    let row = frame.suffix.row?.checked_sub(1)?;
+
+    let file = std::fs::File::open(base_dir.join(&frame.path)).ok()?;
+    let line = BufReader::new(file).lines().nth(row as usize)?.ok()?;
+    if line.trim() != source {
+        return None;
+    }
+
+    let indent = (line.len() - line.trim_start().len()) as u32;
+    Some((start + indent, end + indent))
+}
+
+/// Parses the tracebacks in the lines, in the order they're printed. Frames outside of a
+/// `Traceback (most recent call last):` section, such as the location of a `SyntaxError`, start a
+/// traceback of their own.
+///
+/// The columns of the carets in the files are only filled when `base_dir`, the directory the
+/// traceback was printed in, is given, since reading them depends on the files of that directory.
+pub fn parse_python_traceback<S: AsRef<str>>(
+    lines: &[S],
+    base_dir: Option<&Path>,
+) -> Vec<PythonTraceback> {
+    let mut tracebacks: Vec<PythonTraceback> = Vec::new();
+    let mut current: Option<PythonTraceback> = None;
+    let mut chain = None;
+    // The indentation of the last frame and its source line once seen
+    let mut frame_state: Option<(usize, Option<String>)> = None;
+
+    let new_traceback = |chain: &mut Option<Chain>| PythonTraceback {
+        frames: Vec::new(),
+        exception: None,
+        chain: chain.take(),
+    };
+
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        let trimmed = line.trim();
+
+        if trimmed == TRACEBACK_HEADER {
+            tracebacks.extend(current.take());
+            current = Some(new_traceback(&mut chain));
+            frame_state = None;
+        } else if trimmed == CONTEXT_HEADER || trimmed == CAUSE_HEADER {
+            tracebacks.extend(current.take());
+            chain = Some(if trimmed == CONTEXT_HEADER {
+                Chain::Context
+            } else {
+                Chain::Cause
+            });
+            frame_state = None;
+        } else if let Some(frame) = parse_frame(line, index) {
+            let traceback = match current.as_mut() {
+                Some(traceback) if traceback.exception.is_none() => traceback,
+                _ => {
+                    tracebacks.extend(current.take());
+                    current.insert(new_traceback(&mut chain))
+                }
+            };
+            traceback.frames.push(frame);
+            frame_state = Some((line.len() - line.trim_start().len(), None));
+        } else if let (Some(traceback), Some((indent, source_line))) =
+            (current.as_mut(), frame_state.as_mut())
+        {
+            let line_indent = line.len() - line.trim_start().len();
+            let Some(frame) = traceback.frames.last_mut() else {
+                continue;
+            };
+
+            // Blank lines and notes such as `[Previous line repeated 996 more times]`
+            if trimmed.is_empty() || trimmed.starts_with('[') {
+                continue;
+            } else if line_indent <= *indent {
+                // The end of the frames, eg. `ZeroDivisionError: division by zero`
+                if line_indent == 0 {
+                    traceback.exception = Some(trimmed.to_string());
+                }
+                frame_state = None;
+            } else if let Some(source_line) = source_line.as_deref() {
+                if frame.caret.is_none() {
+                    frame.caret = parse_caret(line, source_line);
+                }
+            } else {
+                frame.source = Some(trimmed.to_string());
+                *source_line = Some(line.to_string());
+            }
+        }
+    }
+
+    tracebacks.extend(current);
+    if let Some(base_dir) = base_dir {
+        for frame in tracebacks.iter_mut().flat_map(|t| t.frames.iter_mut()) {
+            if let Some((col, col_end)) = file_columns(frame, base_dir) {
+                frame.suffix.col = Some(col);
+                frame.suffix.col_end = Some(col_end);
+            }
+        }
+    }
+    tracebacks
+}
+
+#[cfg(test)]
+mod test {
+    use crate::python_traceback::{Chain, parse_python_traceback};
+    use crate::test_util::TempDir;
+    use pretty_assertions::assert_eq;
+
+    type Frame = (String, Option<u32>, Option<String>, Option<(u32, u32)>);
+
+    fn frame(path: &str, row: u32, function: &str, caret: Option<(u32, u32)>) -> Frame {
+        (
+            path.to_string(),
+            Some(row),
+            Some(function.to_string()),
+            caret,
+        )
+    }
+
+    fn frames(output: &str) -> Vec<(Vec<Frame>, Option<String>, Option<Chain>)> {
+        let lines: Vec<&str> = output.lines().collect();
+
+        parse_python_traceback(&lines, None)
+            .into_iter()
+            .map(|traceback| {
+                let frames = traceback
+                    .frames
+                    .into_iter()
+                    .map(|frame| (frame.path, frame.suffix.row, frame.function, frame.caret))
+                    .collect();
+                (frames, traceback.exception, traceback.chain)
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_chained_traceback() {
+        let output = r#"Traceback (most recent call last):
+  File "/app/main.py", line 12, in load
+    return int(value)
+           ^^^^^^^^^^
+ValueError: invalid literal for int() with base 10: 'x'
+
+During handling of the above exception, another exception occurred:
+
+Traceback (most recent call last):
+  File "/app/main.py", line 20, in <module>
+    load("x")
+  File "/app/main.py", line 14, in load
+    raise RuntimeError("bad value") from None
+RuntimeError: bad value
+
+The above exception was the direct cause of the following exception:
+
+Traceback (most recent call last):
+  File "lib/util.py", line 3, in check
+    x = 1 / 0
+        ~~^~~
+ZeroDivisionError: division by zero"#;
+
+        assert_eq!(
+            frames(output),
+            vec![
+                (
+                    vec![frame("/app/main.py", 12, "load", Some((8, 17)))],
+                    Some("ValueError: invalid literal for int() with base 10: 'x'".to_string()),
+                    None
+                ),
+                (
+                    vec![
+                        frame("/app/main.py", 20, "<module>", None),
+                        frame("/app/main.py", 14, "load", None),
+                    ],
+                    Some("RuntimeError: bad value".to_string()),
+                    Some(Chain::Context)
+                ),
+                (
+                    vec![frame("lib/util.py", 3, "check", Some((5, 9)))],
+                    Some("ZeroDivisionError: division by zero".to_string()),
+                    Some(Chain::Cause)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_syntax_error() {
+        let output = r#"  File "/app/broken.py", line 4
+    def f(:
+          ^
+SyntaxError: invalid syntax"#;
+
+        let tracebacks = parse_python_traceback(&output.lines().collect::<Vec<_>>(), None);
+
+        assert_eq!(tracebacks.len(), 1);
+        let frame = &tracebacks[0].frames[0];
+        assert_eq!(frame.path, "/app/broken.py");
+        assert_eq!(frame.suffix.row, Some(4));
+        assert_eq!(frame.function, None);
+        assert_eq!(frame.source.as_deref(), Some("def f(:"));
+        assert_eq!(frame.caret, Some((7, 7)));
+        assert_eq!(frame.suffix.col, None);
+        assert_eq!(
+            tracebacks[0].exception.as_deref(),
+            Some("SyntaxError: invalid syntax")
+        );
+    }
+
+    #[test]
+    pub fn test_frame_lines_and_noise() {
+        let output = r#"some log output
+Traceback (most recent call last):
+  File "a.py", line 1, in f
+    f()
+  [Previous line repeated 996 more times]
+RecursionError: maximum recursion depth exceeded"#;
+
+        let tracebacks = parse_python_traceback(&output.lines().collect::<Vec<_>>(), None);
+
+        assert_eq!(tracebacks.len(), 1);
+        assert_eq!(tracebacks[0].frames[0].line, 3);
+        assert_eq!(tracebacks[0].frames[0].source.as_deref(), Some("f()"));
+        assert_eq!(
+            tracebacks[0].exception.as_deref(),
+            Some("RecursionError: maximum recursion depth exceeded")
+        );
+    }
+
+    #[test]
+    pub fn test_indented_frame_columns() {
+        let dir = TempDir::new("python_traceback");
+        std::fs::write(
+            dir.join("app.py"),
+            "class A:\n    def f(self):\n        return int(\"x\")\n",
+        )
+        .unwrap();
+
+        let output = r#"Traceback (most recent call last):
+  File "app.py", line 3, in f
+    return int("x")
+           ^^^^^^^^
+  File "missing.py", line 3, in f
+    return int("x")
+           ^^^^^^^^
+ValueError: invalid literal for int() with base 10: 'x'"#;
+        let lines: Vec<&str> = output.lines().collect();
+
+        let tracebacks = parse_python_traceback(&lines, None);
+        assert_eq!(tracebacks[0].frames[0].caret, Some((8, 15)));
+        assert_eq!(tracebacks[0].frames[0].suffix.col, None);
+
+        let tracebacks = parse_python_traceback(&lines, Some(&dir));
+        let frames = &tracebacks[0].frames;
+        assert_eq!(frames[0].caret, Some((8, 15)));
+        assert_eq!(
+            (frames[0].suffix.col, frames[0].suffix.col_end),
+            (Some(16), Some(23))
+        );
+        assert_eq!(frames[1].caret, Some((8, 15)));
+        assert_eq!(
+            (frames[1].suffix.col, frames[1].suffix.col_end),
+            (None, None)
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Parsing of Rust panics and `RUST_BACKTRACE=1` backtraces, where the location of each frame is
+//! printed on the line after its symbol. Frames from the standard library and from dependencies are
+//! flagged so they can be skipped, and the `/rustc/<hash>/` paths of the standard library can be
+//! mapped to the local rust-src component.
+
+use crate::{LinkSuffix, get_link_suffix};
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, lua};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `thread 'main' panicked at src/main.rs:10:5:` and the `'message', src/main.rs:10:5` format of
+/// Rust before 1.73
+static PANIC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^thread '(?P<thread>[^']*)' panicked at (?:'(?P<message>.*)', )?(?P<location>.+?):?$",
+    )
+    .unwrap()
+});
+
+/// `  2: fetch_rs::main` and `  2:     0x55d3c4b6c1a5 - fetch_rs::main::h1ae2` with
+/// `RUST_BACKTRACE=full`
+static SYMBOL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?P<index>\d+):\s+(?:0x[0-9a-fA-F]+ - )?(?P<symbol>.+)$").unwrap()
+});
+
+static RUSTC_PATH_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/rustc/[0-9a-f]{7,40}/(?P<path>.+)$").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameOrigin {
+    /// Code of the crate being debugged
+    User,
+    /// The standard library, at `/rustc/<hash>/library/...` or in the rust-src component
+    Std,
+    /// A dependency in `~/.cargo/registry` or `~/.cargo/git`
+    Registry,
+    /// A frame without an `at` line, which are usually frames of the runtime or of the standard
+    /// library built without debug info
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RustLocation {
+    pub path: String,
+    pub suffix: LinkSuffix,
+    /// The path of the file in the local rust-src component for `/rustc/<hash>/` paths
+    pub local_path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RustFrame {
+    pub index: usize,
+    pub symbol: String,
+    pub location: Option<RustLocation>,
+    pub origin: FrameOrigin,
+    /// The 1-based index of the line of the symbol in the parsed lines
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RustPanic {
+    pub thread: Option<String>,
+    pub location: Option<RustLocation>,
+    pub message: Option<String>,
+    pub frames: Vec<RustFrame>,
+}
+
+impl ToObject for RustPanic {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for RustPanic {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+static RUST_SRC_SYSROOT: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let output = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .ok()?;
+    let sysroot = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    (output.status.success() && sysroot.join("lib/rustlib/src/rust/library").is_dir())
+        .then_some(sysroot)
+});
+
+/// Returns the sysroot of the default toolchain when its rust-src component, which contains the
+/// sources of the standard library, is installed. `rustc` is only run the first time.
+pub fn rust_src_sysroot() -> Option<&'static Path> {
+    RUST_SRC_SYSROOT.as_deref()
+}
+
+/// Maps a `/rustc/<hash>/library/...` path to the rust-src component of the sysroot.
+pub fn map_rustc_path(path: &str, sysroot: &Path) -> Option<PathBuf> {
+    let captures = RUSTC_PATH_REGEX.captures(path)?;
+    Some(sysroot.join("lib/rustlib/src/rust").join(&captures["path"]))
+}
+
+fn frame_origin(path: &str) -> FrameOrigin {
+    let path = path.replace('\\', "/");
+    if RUSTC_PATH_REGEX.is_match(&path) || path.contains("/lib/rustlib/src/rust/") {
+        FrameOrigin::Std
+    } else if path.contains("/.cargo/registry/") || path.contains("/.cargo/git/") {
+        FrameOrigin::Registry
+    } else {
+        FrameOrigin::User
+    }
+}
+
+fn parse_location(text: &str, sysroot: Option<&Path>) -> Option<RustLocation> {
+    let text = text.trim();
+    let suffix = get_link_suffix(text)?;
+    suffix.row?;
+    let path = &text[..suffix.suffix.index];
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(RustLocation {
+        path: path.to_string(),
+        suffix,
+        local_path: sysroot
+            .and_then(|sysroot| map_rustc_path(path, sysroot))
+            .map(|path| path.to_string_lossy().into_owned()),
+    })
+}
+
+/// Parses the panics in the lines with the frames of their backtraces. A backtrace without a panic
+/// line, such as one printed by `std::backtrace::Backtrace`, is returned as a panic without a
+/// thread. `sysroot` is used to fill [`RustLocation::local_path`], see [`rust_src_sysroot`].
+pub fn parse_rust_backtrace<S: AsRef<str>>(lines: &[S], sysroot: Option<&Path>) -> Vec<RustPanic> {
+    let mut panics: Vec<RustPanic> = Vec::new();
+    let mut in_message = false;
+    let mut in_backtrace = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.as_ref().trim_end();
+
+        if let Some(captures) = PANIC_REGEX.captures(line) {
+            panics.push(RustPanic {
+                thread: Some(captures["thread"].to_string()),
+                location: parse_location(&captures["location"], sysroot),
+                message: captures.name("message").map(|m| m.as_str().to_string()),
+                frames: Vec::new(),
+            });
+            in_message = captures.name("message").is_none();
+            in_backtrace = false;
+        } else if line.trim() == "stack backtrace:" {
+            if panics.last().is_none_or(|panic| !panic.frames.is_empty()) {
+                panics.push(RustPanic {
+                    thread: None,
+                    location: None,
+                    message: None,
+                    frames: Vec::new(),
+                });
+            }
+            in_message = false;
+            in_backtrace = true;
+        } else if in_backtrace {
+            let Some(panic) = panics.last_mut() else {
+                continue;
+            };
+
+            if let Some(location) = line.trim_start().strip_prefix("at ") {
+                if let Some(frame) = panic.frames.last_mut().filter(|f| f.location.is_none()) {
+                    frame.location = parse_location(location, sysroot);
+                    frame.origin = frame_origin(location.trim());
+                }
+            } else if let Some(captures) = SYMBOL_REGEX.captures(line) {
+                panic.frames.push(RustFrame {
+                    index: captures["index"].parse().unwrap_or_default(),
+                    symbol: captures["symbol"].to_string(),
+                    location: None,
+                    origin: FrameOrigin::Unknown,
+                    line: index + 1,
+                });
+            } else {
+                in_backtrace = false;
+            }
+        } else if in_message {
+            let Some(panic) = panics.last_mut() else {
+                continue;
+            };
+
+            if line.is_empty() || line.starts_with("note: ") {
+                in_message = false;
+            } else {
+                match panic.message.as_mut() {
+                    Some(message) => {
+                        message.push('\n');
+                        message.push_str(line);
+                    }
+                    None => panic.message = Some(line.to_string()),
+                }
+            }
+        }
+    }
+
+    panics
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rust_backtrace::{FrameOrigin, map_rustc_path, parse_rust_backtrace};
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    type Frame<'a> = (usize, &'a str, Option<&'a str>, Option<u32>, FrameOrigin);
+
+    const OUTPUT: &str = "\
+thread 'main' panicked at src/main.rs:10:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/4eb161250e340c8f48f66e2b929ef4a5bed7c181/library/std/src/panicking.rs:665:5
+   1: core::panicking::panic_fmt
+             at /rustc/4eb161250e340c8f48f66e2b929ef4a5bed7c181/library/core/src/panicking.rs:74:14
+   2: regex::compile
+             at /home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/regex-1.11.1/src/lib.rs:12:9
+   3: app::main
+             at ./src/main.rs:10:5
+   4: std::rt::lang_start::{{closure}}
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.";
+
+    #[test]
+    pub fn test_panic_with_backtrace() {
+        let lines: Vec<&str> = OUTPUT.lines().collect();
+        let panics = parse_rust_backtrace(&lines, Some(Path::new("/sysroot")));
+
+        assert_eq!(panics.len(), 1);
+        let panic = &panics[0];
+        assert_eq!(panic.thread.as_deref(), Some("main"));
+        let location = panic.location.as_ref().unwrap();
+        assert_eq!(
+            (
+                location.path.as_str(),
+                location.suffix.row,
+                location.suffix.col
+            ),
+            ("src/main.rs", Some(10), Some(5))
+        );
+        assert_eq!(
+            panic.message.as_deref(),
+            Some("assertion `left == right` failed\n  left: 1\n right: 2")
+        );
+
+        let frames: Vec<Frame> = panic
+            .frames
+            .iter()
+            .map(|frame| {
+                (
+                    frame.index,
+                    frame.symbol.as_str(),
+                    frame.location.as_ref().map(|l| l.path.as_str()),
+                    frame.location.as_ref().and_then(|l| l.suffix.row),
+                    frame.origin,
+                )
+            })
+            .collect();
+        assert_eq!(
+            frames,
+            vec![
+                (
+                    0,
+                    "rust_begin_unwind",
+                    Some(
+                        "/rustc/4eb161250e340c8f48f66e2b929ef4a5bed7c181/library/std/src/panicking.rs"
+                    ),
+                    Some(665),
+                    FrameOrigin::Std
+                ),
+                (
+                    1,
+                    "core::panicking::panic_fmt",
+                    Some(
+                        "/rustc/4eb161250e340c8f48f66e2b929ef4a5bed7c181/library/core/src/panicking.rs"
+                    ),
+                    Some(74),
+                    FrameOrigin::Std
+                ),
+                (
+                    2,
+                    "regex::compile",
+                    Some(
+                        "/home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/regex-1.11.1/src/lib.rs"
+                    ),
+                    Some(12),
+                    FrameOrigin::Registry
+                ),
+                (
+                    3,
+                    "app::main",
+                    Some("./src/main.rs"),
+                    Some(10),
+                    FrameOrigin::User
+                ),
+                (
+                    4,
+                    "std::rt::lang_start::{{closure}}",
+                    None,
+                    None,
+                    FrameOrigin::Unknown
+                ),
+            ]
+        );
+
+        assert_eq!(
+            panic.frames[0]
+                .location
+                .as_ref()
+                .unwrap()
+                .local_path
+                .as_deref(),
+            Some("/sysroot/lib/rustlib/src/rust/library/std/src/panicking.rs")
+        );
+        assert_eq!(panic.frames[3].location.as_ref().unwrap().local_path, None);
+        assert_eq!(panic.frames[3].line, 12);
+    }
+
+    #[test]
+    pub fn test_old_panic_format_and_full_backtrace() {
+        let lines = [
+            "thread '<unnamed>' panicked at 'index out of bounds', src/lib.rs:3:9",
+            "stack backtrace:",
+            "   0:     0x55d3c4b6c1a5 - app::get::h1ae2f1c3e7a1b2c3",
+            "                               at /app/src/lib.rs:3:9",
+        ];
+
+        let panics = parse_rust_backtrace(&lines, None);
+
+        assert_eq!(panics.len(), 1);
+        assert_eq!(panics[0].thread.as_deref(), Some("<unnamed>"));
+        assert_eq!(panics[0].message.as_deref(), Some("index out of bounds"));
+        assert_eq!(panics[0].location.as_ref().unwrap().path, "src/lib.rs");
+        assert_eq!(panics[0].frames[0].symbol, "app::get::h1ae2f1c3e7a1b2c3");
+        assert_eq!(
+            panics[0].frames[0].location.as_ref().unwrap().path,
+            "/app/src/lib.rs"
+        );
+    }
+
+    #[test]
+    pub fn test_map_rustc_path() {
+        assert_eq!(
+            map_rustc_path(
+                "/rustc/4eb161250e340c8f48f66e2b929ef4a5bed7c181/library/core/src/option.rs",
+                Path::new("/toolchain")
+            ),
+            Some(
+                Path::new("/toolchain/lib/rustlib/src/rust/library/core/src/option.rs")
+                    .to_path_buf()
+            )
+        );
+        assert_eq!(map_rustc_path("src/main.rs", Path::new("/toolchain")), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Parsing of the results of SARIF logs, the format emitted by linters and static analyzers, into
+//! diagnostics.
+
+use crate::diagnostics::{Diagnostic, Severity, location_link, range_location, uri_path};
+use crate::format::{Location, LocationFormat, format_location};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Artifact locations can refer to base URIs and artifacts, deeper chains are considered cycles.
+const MAX_URI_DEPTH: usize = 8;
+
+#[derive(Deserialize)]
+struct RawLog {
+    runs: Vec<RawRun>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRun {
+    tool: RawTool,
+    #[serde(default)]
+    results: Vec<RawResult>,
+    #[serde(default)]
+    artifacts: Vec<RawArtifact>,
+    #[serde(default)]
+    original_uri_base_ids: HashMap<String, RawArtifactLocation>,
+}
+
+#[derive(Deserialize)]
+struct RawTool {
+    driver: RawDriver,
+}
+
+#[derive(Deserialize)]
+struct RawDriver {
+    name: String,
+    #[serde(default)]
+    rules: Vec<RawRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRule {
+    id: String,
+    default_configuration: Option<RawConfiguration>,
+}
+
+#[derive(Deserialize)]
+struct RawConfiguration {
+    level: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawArtifact {
+    location: Option<RawArtifactLocation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawArtifactLocation {
+    uri: Option<String>,
+    uri_base_id: Option<String>,
+    index: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawResult {
+    rule_id: Option<String>,
+    rule_index: Option<usize>,
+    level: Option<String>,
+    message: RawMessage,
+    #[serde(default)]
+    locations: Vec<RawLocation>,
+    #[serde(default)]
+    related_locations: Vec<RawLocation>,
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    text: Option<String>,
+    markdown: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLocation {
+    physical_location: Option<RawPhysicalLocation>,
+    message: Option<RawMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPhysicalLocation {
+    artifact_location: RawArtifactLocation,
+    region: Option<RawRegion>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRegion {
+    start_line: Option<u32>,
+    start_column: Option<u32>,
+    end_line: Option<u32>,
+    /// Columns are 1-based and the end is exclusive
+    end_column: Option<u32>,
+}
+
+impl RawMessage {
+    fn text(&self) -> Option<&str> {
+        self.text.as_deref().or(self.markdown.as_deref())
+    }
+}
+
+fn severity(level: &str) -> Severity {
+    match level {
+        "error" => Severity::Error,
+        "note" => Severity::Info,
+        "none" => Severity::Hint,
+        _ => Severity::Warning,
+    }
+}
+
+impl RawRun {
+    /// Returns the URI of the artifact, prefixed by its base URI when the base is known.
+    fn uri(&self, location: &RawArtifactLocation, depth: usize) -> Option<String> {
+        if depth > MAX_URI_DEPTH {
+            return None;
+        }
+
+        let Some(uri) = location.uri.clone() else {
+            // The location only refers to an artifact of the run
+            let artifact = self.artifacts.get(location.index?)?;
+            return self.uri(artifact.location.as_ref()?, depth + 1);
+        };
+
+        let base = location
+            .uri_base_id
+            .as_ref()
+            .and_then(|id| self.original_uri_base_ids.get(id))
+            .and_then(|base| self.uri(base, depth + 1));
+        Some(match base {
+            Some(base) if !uri.contains("://") => {
+                format!(
+                    "{}/{}",
+                    base.trim_end_matches('/'),
+                    uri.trim_start_matches('/')
+                )
+            }
+            _ => uri,
+        })
+    }
+
+    /// Returns the location of the region, or of the first line of the file for results about the
+    /// whole file.
+    fn location(&self, location: &RawLocation) -> Option<Location> {
+        let physical = location.physical_location.as_ref()?;
+        let path = uri_path(&self.uri(&physical.artifact_location, 0)?);
+
+        Some(match &physical.region {
+            Some(RawRegion {
+                start_line: Some(row),
+                start_column,
+                end_line,
+                end_column,
+            }) => range_location(
+                path,
+                (*row, *start_column),
+                (end_line.unwrap_or(*row), *end_column),
+            ),
+            _ => Location {
+                path,
+                row: 1,
+                col: None,
+                row_end: None,
+                col_end: None,
+            },
+        })
+    }
+}
+
+/// Parses the results of all the runs of a SARIF log, with a diagnostic for each location of a
+/// result. Results without a location in a file are skipped. The messages of related locations
+/// are returned as notes.
+pub fn parse_sarif(json: &str) -> Result<Vec<Diagnostic>, String> {
+    let log: RawLog = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut diagnostics = Vec::new();
+
+    for run in &log.runs {
+        for result in &run.results {
+            let rule = result
+                .rule_index
+                .and_then(|index| run.tool.driver.rules.get(index))
+                .or_else(|| {
+                    let id = result.rule_id.as_ref()?;
+                    run.tool.driver.rules.iter().find(|rule| &rule.id == id)
+                });
+            let level = result
+                .level
+                .as_deref()
+                .or_else(|| rule?.default_configuration.as_ref()?.level.as_deref());
+            let notes: Vec<String> = result
+                .related_locations
+                .iter()
+                .filter_map(|related| {
+                    let location = run.location(related)?;
+                    let message = related.message.as_ref()?.text()?;
+                    Some(format!(
+                        "{}: {}",
+                        format_location(&location, LocationFormat::Colon),
+                        message
+                    ))
+                })
+                .collect();
+
+            for location in result.locations.iter().filter_map(|l| run.location(l)) {
+                diagnostics.push(Diagnostic {
+                    link: location_link(&location),
+                    severity: level.map_or(Severity::Warning, severity),
+                    message: result.message.text().unwrap_or_default().to_string(),
+                    code: result
+                        .rule_id
+                        .clone()
+                        .or_else(|| rule.map(|rule| rule.id.clone())),
+                    source: Some(run.tool.driver.name.clone()),
+                    label: None,
+                    notes: notes.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::diagnostics::Severity;
+    use crate::sarif::parse_sarif;
+    use pretty_assertions::assert_eq;
+
+    /// The path, severity, code, row, column, end row and end column of a diagnostic
+    type Entry = (
+        String,
+        Severity,
+        Option<String>,
+        (Option<u32>, Option<u32>, Option<u32>, Option<u32>),
+    );
+
+    #[test]
+    pub fn test_parse_sarif() {
+        let json = r#"{
+  "version": "2.1.0",
+  "runs": [{
+    "tool": {"driver": {"name": "eslint", "rules": [
+      {"id": "no-unused-vars", "defaultConfiguration": {"level": "error"}},
+      {"id": "eqeqeq"}
+    ]}},
+    "originalUriBaseIds": {
+      "ROOT": {"uri": "file:///home/user/app/"},
+      "SRCROOT": {"uri": "src/", "uriBaseId": "ROOT"}
+    },
+    "artifacts": [{"location": {"uri": "lib/my%20file.js", "uriBaseId": "SRCROOT"}}],
+    "results": [
+      {
+        "ruleId": "no-unused-vars",
+        "ruleIndex": 0,
+        "message": {"text": "'x' is assigned a value but never used."},
+        "locations": [{"physicalLocation": {
+          "artifactLocation": {"uri": "index.js", "uriBaseId": "SRCROOT"},
+          "region": {"startLine": 3, "startColumn": 7, "endLine": 3, "endColumn": 8}
+        }}],
+        "relatedLocations": [{
+          "physicalLocation": {"artifactLocation": {"uri": "index.js"}, "region": {"startLine": 1}},
+          "message": {"text": "declared here"}
+        }]
+      },
+      {
+        "ruleId": "eqeqeq",
+        "level": "note",
+        "message": {"text": "Expected '===' and instead saw '=='."},
+        "locations": [{"physicalLocation": {
+          "artifactLocation": {"index": 0},
+          "region": {"startLine": 10, "startColumn": 5, "endLine": 12, "endColumn": 2}
+        }}]
+      },
+      {
+        "ruleId": "eqeqeq",
+        "message": {"text": "file level"},
+        "locations": [{"physicalLocation": {"artifactLocation": {"uri": "file:///C:/app/a.js"}}}]
+      },
+      {"ruleId": "eqeqeq", "message": {"text": "no location"}}
+    ]
+  }]
+}"#;
+
+        let diagnostics = parse_sarif(json).unwrap();
+        let results: Vec<Entry> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let suffix = diagnostic.link.suffix.as_ref().unwrap();
+                (
+                    diagnostic.link.path.text.clone(),
+                    diagnostic.severity,
+                    diagnostic.code.clone(),
+                    (suffix.row, suffix.col, suffix.row_end, suffix.col_end),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                (
+                    "/home/user/app/src/index.js".to_string(),
+                    Severity::Error,
+                    Some("no-unused-vars".to_string()),
+                    (Some(3), Some(7), None, Some(7))
+                ),
+                (
+                    "/home/user/app/src/lib/my file.js".to_string(),
+                    Severity::Info,
+                    Some("eqeqeq".to_string()),
+                    (Some(10), Some(5), Some(12), Some(1))
+                ),
+                (
+                    "C:/app/a.js".to_string(),
+                    Severity::Warning,
+                    Some("eqeqeq".to_string()),
+                    (Some(1), None, None, None)
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[0].source.as_deref(), Some("eslint"));
+        assert_eq!(diagnostics[0].notes, vec!["index.js:1: declared here"]);
+    }
+
+    #[test]
+    pub fn test_parse_invalid_sarif() {
+        assert!(parse_sarif("{}").is_err());
+        assert!(parse_sarif("not json").is_err());
+    }
+}
@@ -0,0 +1,474 @@
+//! Resolution of locations in compiled javascript, such as `dist/bundle.js:1:48213` in a node
+//! stack trace, to the original source using the file's source map. The map is found through the
+//! `sourceMappingURL` comment of the file, which may be an inline `data:` URL, or otherwise the
+//! sibling `.map` file.
+
+use crate::format::Location;
+use crate::permalink::percent_decode;
+use nvim_oxi::conversion::{Error as ConversionError, ToObject};
+use nvim_oxi::serde::Serializer;
+use nvim_oxi::{Object, lua};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A location in a generated file and the location in the original source it was compiled from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MappedLocation {
+    pub generated: Location,
+    pub original: Location,
+    /// The original name of the symbol at the location, when the map has one
+    pub name: Option<String>,
+}
+
+impl ToObject for MappedLocation {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+impl lua::Pushable for MappedLocation {
+    unsafe fn push(self, lstate: *mut lua::ffi::lua_State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+/// The fields of a version 3 source map that are needed to map locations.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    version: u32,
+    #[serde(default)]
+    source_root: Option<String>,
+    #[serde(default)]
+    sources: Vec<Option<String>>,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    mappings: String,
+}
+
+/// A mapping from a column of a generated line, all values are 0-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    generated_col: u32,
+    /// The source, row, column and name of the original location
+    original: Option<(usize, u32, u32, Option<usize>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMap {
+    /// The paths of the sources with the source root applied
+    sources: Vec<String>,
+    names: Vec<String>,
+    /// The segments of each generated line, sorted by column
+    lines: Vec<Vec<Segment>>,
+}
+
+impl SourceMap {
+    /// Parses a version 3 source map. Index maps with `sections` aren't supported.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let raw: RawSourceMap = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if raw.version != 3 {
+            return Err(format!("unsupported source map version: {}", raw.version));
+        }
+
+        let source_root = raw.source_root.unwrap_or_default();
+        let sources = raw
+            .sources
+            .into_iter()
+            .map(|source| {
+                let source = source.unwrap_or_default();
+                if source_root.is_empty() || source_root.ends_with('/') {
+                    format!("{}{}", source_root, source)
+                } else {
+                    format!("{}/{}", source_root, source)
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            sources,
+            names: raw.names,
+            lines: decode_mappings(&raw.mappings)?,
+        })
+    }
+
+    /// Returns the source, 1-based row and column and name of the original location of the 1-based
+    /// generated `row` and `col`. Without a column the first mapping of the row is used.
+    pub fn lookup(&self, row: u32, col: Option<u32>) -> Option<(&str, u32, u32, Option<&str>)> {
+        let segments = self.lines.get(row.checked_sub(1)? as usize)?;
+        let segment = match col {
+            Some(col) => {
+                let col = col.saturating_sub(1);
+                let index = segments.partition_point(|segment| segment.generated_col <= col);
+                segments[..index].last()?
+            }
+            None => segments.iter().find(|segment| segment.original.is_some())?,
+        };
+
+        let (source, row, col, name) = segment.original?;
+        Some((
+            self.sources.get(source)?.as_str(),
+            row + 1,
+            col + 1,
+            name.and_then(|name| self.names.get(name))
+                .map(String::as_str),
+        ))
+    }
+}
+
+/// Decodes the base64 VLQ `mappings` of a source map into the segments of each generated line.
+fn decode_mappings(mappings: &str) -> Result<Vec<Vec<Segment>>, String> {
+    // The fields other than the generated column are relative to the previous segment of any line
+    let (mut source, mut row, mut col, mut name) = (0i64, 0i64, 0i64, 0i64);
+
+    mappings
+        .split(';')
+        .map(|line| {
+            let mut generated_col = 0i64;
+            let mut segments = line
+                .split(',')
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| {
+                    // Malformed maps may add up to negative values
+                    let invalid = || format!("invalid source map segment: {}", segment);
+                    let fields = decode_vlq(segment)?;
+                    generated_col += fields[0];
+
+                    let original = match fields.len() {
+                        1 => None,
+                        4 | 5 => {
+                            source += fields[1];
+                            row += fields[2];
+                            col += fields[3];
+                            let segment_name = if fields.len() == 5 {
+                                name += fields[4];
+                                Some(usize::try_from(name).map_err(|_| invalid())?)
+                            } else {
+                                None
+                            };
+                            Some((
+                                usize::try_from(source).map_err(|_| invalid())?,
+                                u32::try_from(row).map_err(|_| invalid())?,
+                                u32::try_from(col).map_err(|_| invalid())?,
+                                segment_name,
+                            ))
+                        }
+                        _ => return Err(invalid()),
+                    };
+                    Ok(Segment {
+                        generated_col: u32::try_from(generated_col).map_err(|_| invalid())?,
+                        original,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            segments.sort_by_key(|segment| segment.generated_col);
+            Ok(segments)
+        })
+        .collect()
+}
+
+fn base64_value(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes the base64 VLQ values of a segment.
+fn decode_vlq(segment: &str) -> Result<Vec<i64>, String> {
+    let invalid = || format!("invalid source map segment: {}", segment);
+    let mut values = Vec::new();
+    let (mut value, mut shift) = (0i64, 0);
+
+    for c in segment.bytes() {
+        let digit = base64_value(c).ok_or_else(invalid)? as i64;
+        value += (digit & 0b11111) << shift;
+        if digit & 0b100000 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return Err(invalid());
+            }
+            continue;
+        }
+
+        // The lowest bit is the sign
+        values.push(if value & 1 == 1 {
+            -(value >> 1)
+        } else {
+            value >> 1
+        });
+        (value, shift) = (0, 0);
+    }
+
+    if shift != 0 || values.is_empty() {
+        return Err(invalid());
+    }
+    Ok(values)
+}
+
+/// Decodes standard base64, as used by inline source maps.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+
+    for c in text.trim_end_matches('=').bytes() {
+        buffer = (buffer << 6) | base64_value(c)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Returns the value of the last `sourceMappingURL` comment of the generated file.
+fn source_mapping_url(text: &str) -> Option<&str> {
+    text.lines().rev().find_map(|line| {
+        let line = line.trim();
+        let comment = line
+            .strip_prefix("//# ")
+            .or_else(|| line.strip_prefix("//@ "))
+            .or_else(|| line.strip_prefix("/*# ").and_then(|c| c.strip_suffix("*/")))?;
+        Some(comment.strip_prefix("sourceMappingURL=")?.trim())
+    })
+}
+
+/// Reads the source map of the generated file and returns it with the directory its sources are
+/// relative to.
+pub fn find_source_map(file: &Path) -> Result<(SourceMap, PathBuf), String> {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let text = fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+
+    let (json, map_dir) = match source_mapping_url(&text) {
+        Some(url) if url.starts_with("data:") => {
+            let (header, data) = url.split_once(',').ok_or("invalid source map data URL")?;
+            let json = if header.ends_with(";base64") {
+                decode_base64(data)
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .ok_or("invalid base64 in source map data URL")?
+            } else {
+                percent_decode(data)
+            };
+            (json, dir.to_path_buf())
+        }
+        Some(url) => {
+            let path = dir.join(percent_decode(url.strip_prefix("file://").unwrap_or(url)));
+            let json =
+                fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            (json, path.parent().unwrap_or(dir).to_path_buf())
+        }
+        None => {
+            let mut path = file.as_os_str().to_owned();
+            path.push(".map");
+            let json = fs::read_to_string(&path)
+                .map_err(|e| format!("{}: {}", Path::new(&path).display(), e))?;
+            (json, dir.to_path_buf())
+        }
+    };
+
+    Ok((SourceMap::parse(&json)?, map_dir))
+}
+
+/// Resolves a source of the map relative to the directory of the map. Bundler URLs such as
+/// `webpack://app/./src/index.ts` are relative to the directory of the map too.
+fn source_path(source: &str, map_dir: &Path) -> PathBuf {
+    let source = if let Some(path) = source.strip_prefix("file://") {
+        path.to_string()
+    } else if let Some((_, rest)) = source
+        .split_once("://")
+        .filter(|(scheme, _)| scheme.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        // Drop the namespace of the bundler
+        let path = rest.split_once('/').map_or(rest, |(_, path)| path);
+        path.trim_start_matches('/').to_string()
+    } else {
+        source.to_string()
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in map_dir.join(source).components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// Maps the 1-based `row` and `col` of the generated file to the original source.
+pub fn resolve_location(
+    file: &Path,
+    row: u32,
+    col: Option<u32>,
+) -> Result<Option<MappedLocation>, String> {
+    let (map, map_dir) = find_source_map(file)?;
+
+    Ok(map.lookup(row, col).map(
+        |(source, original_row, original_col, name)| MappedLocation {
+            generated: Location {
+                path: file.to_string_lossy().into_owned(),
+                row,
+                col,
+                row_end: None,
+                col_end: None,
+            },
+            original: Location {
+                path: source_path(source, &map_dir).to_string_lossy().into_owned(),
+                row: original_row,
+                col: Some(original_col),
+                row_end: None,
+                col_end: None,
+            },
+            name: name.map(str::to_string),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sourcemap::{
+        SourceMap, decode_base64, decode_mappings, decode_vlq, resolve_location, source_path,
+    };
+    use crate::test_util::TempDir;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    // The map of `dist/*.js` to `src/greet.ts`:
+    //
+    // export function greet(name: string) {
+    //   throw new Error(`hello ${name}`);
+    // }
+    const MAP: &str = r#"{
+        "version": 3,
+        "sources": ["../src/greet.ts"],
+        "names": ["greet", "name"],
+        "mappings": "AAAO,SAASA,MAAMC,MAAc;AAClC,QAAM,IAAI,MAAM,SAASA,IAAI,EAAE;AACjC"
+    }"#;
+
+    #[test]
+    pub fn test_decode_vlq() {
+        assert_eq!(decode_vlq("AAAA").unwrap(), vec![0, 0, 0, 0]);
+        assert_eq!(decode_vlq("SAASA").unwrap(), vec![9, 0, 0, 9, 0]);
+        assert_eq!(decode_vlq("D").unwrap(), vec![-1]);
+        assert_eq!(decode_vlq("2HwB").unwrap(), vec![123, 24]);
+        assert!(decode_vlq("g").is_err());
+        assert!(decode_vlq("A!").is_err());
+    }
+
+    #[test]
+    pub fn test_negative_mappings() {
+        assert!(decode_mappings("AAAA;ACAA").is_ok());
+        // The generated column, source, row, column and name going below zero
+        for mappings in ["D", "ADAA", "AADA;AACA", "AAAD", "AAAAD"] {
+            assert!(decode_mappings(mappings).is_err(), "{}", mappings);
+        }
+    }
+
+    #[test]
+    pub fn test_lookup() {
+        let map = SourceMap::parse(MAP).unwrap();
+
+        assert_eq!(
+            map.lookup(1, Some(1)),
+            Some(("../src/greet.ts", 1, 8, None))
+        );
+        assert_eq!(
+            map.lookup(1, Some(12)),
+            Some(("../src/greet.ts", 1, 17, Some("greet")))
+        );
+        assert_eq!(map.lookup(2, None), Some(("../src/greet.ts", 2, 3, None)));
+        assert_eq!(
+            map.lookup(2, Some(9)),
+            Some(("../src/greet.ts", 2, 9, None))
+        );
+        assert_eq!(map.lookup(10, Some(1)), None);
+    }
+
+    #[test]
+    pub fn test_resolve_location() {
+        let dir = TempDir::new("sourcemap");
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+
+        let code = "export function greet(name) {\n  throw new Error(`hello ${name}`);\n}\n";
+        std::fs::write(dir.join("dist/sibling.js"), code).unwrap();
+        std::fs::write(dir.join("dist/sibling.js.map"), MAP).unwrap();
+
+        let inline = format!(
+            "{}//# sourceMappingURL=data:application/json;base64,{}\n",
+            code,
+            base64(MAP.as_bytes())
+        );
+        std::fs::write(dir.join("dist/inline.js"), inline).unwrap();
+
+        for name in ["sibling.js", "inline.js"] {
+            let file = dir.join("dist").join(name);
+            let location = resolve_location(&file, 2, Some(9)).unwrap().unwrap();
+
+            assert_eq!(location.generated.path, file.to_string_lossy());
+            assert_eq!(
+                (location.generated.row, location.generated.col),
+                (2, Some(9))
+            );
+            assert_eq!(
+                Path::new(&location.original.path),
+                dir.join("src/greet.ts").as_path()
+            );
+            assert_eq!((location.original.row, location.original.col), (2, Some(9)));
+        }
+
+        assert!(resolve_location(&dir.join("dist/missing.js"), 1, None).is_err());
+    }
+
+    #[test]
+    pub fn test_source_path() {
+        let map_dir = Path::new("/app/dist");
+
+        assert_eq!(
+            source_path("../src/index.ts", map_dir),
+            Path::new("/app/src/index.ts")
+        );
+        assert_eq!(
+            source_path("webpack://app/./src/index.ts", map_dir),
+            Path::new("/app/dist/src/index.ts")
+        );
+        assert_eq!(
+            source_path("file:///home/src/index.ts", map_dir),
+            Path::new("/home/src/index.ts")
+        );
+    }
+
+    #[test]
+    pub fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("aGk").unwrap(), b"hi");
+        assert_eq!(decode_base64("a!"), None);
+    }
+
+    fn base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        bytes
+            .chunks(3)
+            .flat_map(|chunk| {
+                let value = chunk.iter().enumerate().fold(0u32, |value, (i, byte)| {
+                    value | (*byte as u32) << (16 - 8 * i)
+                });
+                (0..=chunk.len())
+                    .map(move |i| ALPHABET[(value >> (18 - 6 * i)) as usize & 63] as char)
+            })
+            .collect()
+    }
+}
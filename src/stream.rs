@@ -0,0 +1,200 @@
+//! Streaming link detection over anything implementing [`BufRead`], such as log files that are too
+//! large to load at once. Lines are read one at a time, invalid UTF-8 is replaced lossily and the
+//! indices of the returned links are byte offsets into the raw line.
+
+use crate::offsets::OffsetMap;
+use crate::{LinkPartialRange, OperatingSystem, ParsedLink, detect_links};
+use std::io::{self, BufRead};
+
+/// A link read by a [`LinkReader`]. The text of the parts of the link is decoded while their
+/// indices are byte offsets into the raw line, so the raw ends of the parts are kept as well since
+/// a part containing invalid UTF-8 has a different length in the raw line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamLink {
+    /// The 1-based number of the line of the link
+    pub line_number: usize,
+    pub link: ParsedLink,
+    /// The exclusive end of the path in the raw line
+    pub path_end: usize,
+    pub prefix_end: Option<usize>,
+    pub suffix_end: Option<usize>,
+}
+
+impl StreamLink {
+    fn new(line_number: usize, link: ParsedLink, offsets: &OffsetMap) -> Self {
+        let end = |range: &LinkPartialRange| offsets.to_raw_end(range.index + range.text.len());
+
+        Self {
+            line_number,
+            path_end: end(&link.path),
+            prefix_end: link.prefix.as_ref().map(end),
+            suffix_end: link.suffix.as_ref().map(|suffix| end(&suffix.suffix)),
+            link: offsets.map_link(link),
+        }
+    }
+}
+
+/// An iterator over the links in a reader. Lines may end with `\n` or `\r\n`.
+pub struct LinkReader<R> {
+    reader: R,
+    os: OperatingSystem,
+    line_number: usize,
+    buffer: Vec<u8>,
+    pending: std::vec::IntoIter<StreamLink>,
+}
+
+impl<R: BufRead> LinkReader<R> {
+    pub fn new(reader: R, os: OperatingSystem) -> Self {
+        Self {
+            reader,
+            os,
+            line_number: 0,
+            buffer: Vec::new(),
+            pending: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for LinkReader<R> {
+    type Item = io::Result<StreamLink>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(link) = self.pending.next() {
+                return Some(Ok(link));
+            }
+
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err)),
+            }
+            self.line_number += 1;
+
+            let line = trim_line_ending(&self.buffer);
+            let (text, offsets) = decode_lossy(line);
+            self.pending = detect_links(&text, self.os)
+                .into_iter()
+                .map(|link| StreamLink::new(self.line_number, link, &offsets))
+                .collect::<Vec<_>>()
+                .into_iter();
+        }
+    }
+}
+
+/// Removes a trailing `\n` or `\r\n` from the line.
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Decodes the line as UTF-8, replacing invalid sequences with `U+FFFD`. The replacement character
+/// takes up a different number of bytes than the sequence it replaces, so the offsets of the valid
+/// segments in the raw line are recorded.
+fn decode_lossy(line: &[u8]) -> (String, OffsetMap) {
+    let mut text = String::with_capacity(line.len());
+    let mut offsets = OffsetMap::default();
+    let mut raw_index = 0;
+
+    for chunk in line.utf8_chunks() {
+        offsets.push(text.len(), raw_index, chunk.valid().len());
+        text.push_str(chunk.valid());
+        raw_index += chunk.valid().len();
+
+        if !chunk.invalid().is_empty() {
+            offsets.push(text.len(), raw_index, chunk.invalid().len());
+            text.push(char::REPLACEMENT_CHARACTER);
+            raw_index += chunk.invalid().len();
+        }
+    }
+
+    (text, offsets)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::OperatingSystem;
+    use crate::stream::{LinkReader, StreamLink};
+    use pretty_assertions::assert_eq;
+    use std::io::Cursor;
+
+    fn read_links(input: &[u8]) -> Vec<StreamLink> {
+        LinkReader::new(Cursor::new(input), OperatingSystem::Linux)
+            .collect::<std::io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn test_line_numbers_and_crlf() {
+        let links = read_links(b"src/a.rs:1\r\n\r\nno links\r\nsrc/b.rs:2:3 and src/c.rs\r\n");
+
+        let summary: Vec<(usize, &str, Option<&str>)> = links
+            .iter()
+            .map(|link| {
+                (
+                    link.line_number,
+                    link.link.path.text.as_str(),
+                    link.link.suffix.as_ref().map(|s| s.suffix.text.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "src/a.rs", Some(":1")),
+                (4, "src/b.rs", Some(":2:3")),
+                (4, "src/c.rs", None),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_utf8_keeps_byte_offsets() {
+        let input = b"\xff\xfe caf\xe9 src/main.rs:10:5\n";
+        let links = read_links(input);
+
+        assert_eq!(links.len(), 1);
+        let link = &links[0];
+        assert_eq!(link.line_number, 1);
+        assert_eq!(link.link.path.text, "src/main.rs");
+        assert_eq!(&input[link.link.path.index..link.path_end], b"src/main.rs");
+
+        let suffix = &link.link.suffix.as_ref().unwrap().suffix;
+        assert_eq!(&input[suffix.index..link.suffix_end.unwrap()], b":10:5");
+    }
+
+    #[test]
+    pub fn test_invalid_utf8_inside_path() {
+        let input = b"at src/caf\xe9/main.rs:3 and ./b\xff\n";
+        let links = read_links(input);
+
+        let ranges: Vec<(&str, &[u8])> = links
+            .iter()
+            .map(|link| {
+                (
+                    link.link.path.text.as_str(),
+                    &input[link.link.path.index..link.path_end],
+                )
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("src/caf\u{fffd}/main.rs", &b"src/caf\xe9/main.rs"[..]),
+                ("./b\u{fffd}", &b"./b\xff"[..]),
+            ]
+        );
+        let suffix = &links[0].link.suffix.as_ref().unwrap().suffix;
+        assert_eq!(&input[suffix.index..links[0].suffix_end.unwrap()], b":3");
+    }
+
+    #[test]
+    pub fn test_last_line_without_newline() {
+        let links = read_links(b"first\nsecond ./README.md");
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].line_number, 2);
+        assert_eq!(links[0].link.path.text, "./README.md");
+    }
+}
@@ -1,8 +1,8 @@
 #[cfg(test)]
 mod link_parsing_tests {
     use crate::{
-        LinkPartialRange, LinkSuffix, OperatingSystem, detect_link_suffixes, detect_links,
-        get_link_suffix, remove_link_query_string, remove_link_suffix,
+        LinkPartialRange, LinkSuffix, OperatingSystem, ParsedLink, detect_link_suffixes,
+        detect_links, get_link_suffix, remove_link_query_string, remove_link_suffix,
     };
     use pretty_assertions::assert_eq;
     use std::fmt;
@@ -964,10 +964,136 @@ mod link_parsing_tests {
         },
     ];
 
+    struct TestLinkWithSpaces {
+        link: &'static str,
+        path: &'static str,
+        prefix: Option<&'static str>,
+        suffix: Option<&'static str>,
+    }
+
+    const TEST_LINKS_WITH_SPACES: &[TestLinkWithSpaces] = &[
+        // Quoted
+        TestLinkWithSpaces {
+            link: "\"foo bar\",339",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some("\",339"),
+        },
+        TestLinkWithSpaces {
+            link: "\"foo bar\", line 339",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some("\", line 339"),
+        },
+        TestLinkWithSpaces {
+            link: "\"foo bar\", line 339, col 12",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some("\", line 339, col 12"),
+        },
+        TestLinkWithSpaces {
+            link: "\"foo bar\": line 339",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some("\": line 339"),
+        },
+        TestLinkWithSpaces {
+            link: "\"foo bar\" on line 339, column 12",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some("\" on line 339, column 12"),
+        },
+        TestLinkWithSpaces {
+            link: "'foo bar', line 339",
+            path: "foo bar",
+            prefix: Some("'"),
+            suffix: Some("', line 339"),
+        },
+        TestLinkWithSpaces {
+            link: "'foo bar' line 339 column 12",
+            path: "foo bar",
+            prefix: Some("'"),
+            suffix: Some("' line 339 column 12"),
+        },
+        TestLinkWithSpaces {
+            link: "\"foo bar\":339:12",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some(":339:12"),
+        },
+        TestLinkWithSpaces {
+            link: "\"foo bar\" (339, 12)",
+            path: "foo bar",
+            prefix: Some("\""),
+            suffix: Some(" (339, 12)"),
+        },
+        TestLinkWithSpaces {
+            link: "\"My Documents/foo bar.txt\", line 339",
+            path: "My Documents/foo bar.txt",
+            prefix: Some("\""),
+            suffix: Some("\", line 339"),
+        },
+        TestLinkWithSpaces {
+            link: "\"My Documents/foo\"",
+            path: "My Documents/foo",
+            prefix: Some("\""),
+            suffix: None,
+        },
+        TestLinkWithSpaces {
+            link: "'/My Documents/foo'",
+            path: "/My Documents/foo",
+            prefix: Some("'"),
+            suffix: None,
+        },
+        // Escaped
+        TestLinkWithSpaces {
+            link: "foo\\ bar:339",
+            path: "foo\\ bar",
+            prefix: None,
+            suffix: Some(":339"),
+        },
+        TestLinkWithSpaces {
+            link: "foo\\ bar:339:12",
+            path: "foo\\ bar",
+            prefix: None,
+            suffix: Some(":339:12"),
+        },
+        TestLinkWithSpaces {
+            link: "My\\ Documents/foo\\ bar.txt(339, 12)",
+            path: "My\\ Documents/foo\\ bar.txt",
+            prefix: None,
+            suffix: Some("(339, 12)"),
+        },
+        TestLinkWithSpaces {
+            link: "My\\ Documents/foo",
+            path: "My\\ Documents/foo",
+            prefix: None,
+            suffix: None,
+        },
+        TestLinkWithSpaces {
+            link: "/My\\ Documents/foo\\ bar",
+            path: "/My\\ Documents/foo\\ bar",
+            prefix: None,
+            suffix: None,
+        },
+    ];
+
     fn get_test_links_with_suffix() -> Vec<&'static TestLink> {
         TEST_LINKS.iter().filter(|l| l.suffix.is_some()).collect()
     }
 
+    /// Resets the confidence of the links, for tests that generate their expected links from the
+    /// tables above. The confidence is covered by the tests in `confidence.rs`.
+    fn without_confidence(links: Vec<ParsedLink>) -> Vec<ParsedLink> {
+        links
+            .into_iter()
+            .map(|link| ParsedLink {
+                confidence: 0,
+                ..link
+            })
+            .collect()
+    }
+
     #[test]
     fn test_remove_link_suffix() {
         for test_link in TEST_LINKS {
@@ -1141,6 +1267,7 @@ mod link_parsing_tests {
                         text: "(1, 2)".to_string(),
                     },
                 }),
+                confidence: 50,
             },
             crate::ParsedLink {
                 path: LinkPartialRange {
@@ -1158,6 +1285,7 @@ mod link_parsing_tests {
                         text: "[3, 4]".to_string(),
                     },
                 }),
+                confidence: 50,
             },
             crate::ParsedLink {
                 path: LinkPartialRange {
@@ -1178,6 +1306,7 @@ mod link_parsing_tests {
                         text: "\" on line 5".to_string(),
                     },
                 }),
+                confidence: 65,
             },
         ];
 
@@ -1208,6 +1337,7 @@ mod link_parsing_tests {
                     text: "\", line 5, col 6".to_string(),
                 },
             }),
+            confidence: 65,
         }];
 
         assert_eq!(expected, results);
@@ -1237,6 +1367,7 @@ mod link_parsing_tests {
                     text: "\", line 5, col 6".to_string(),
                 },
             }),
+            confidence: 65,
         }];
 
         assert_eq!(results, expected);
@@ -1255,6 +1386,7 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 40,
             },
             crate::ParsedLink {
                 path: LinkPartialRange {
@@ -1275,6 +1407,7 @@ mod link_parsing_tests {
                         text: "\", line 5, col 6".to_string(),
                     },
                 }),
+                confidence: 65,
             },
         ];
 
@@ -1294,6 +1427,7 @@ mod link_parsing_tests {
             },
             prefix: None,
             suffix: None,
+            confidence: 40,
         }];
 
         assert_eq!(results, expected);
@@ -1318,6 +1452,7 @@ mod link_parsing_tests {
                     text: ":400".to_string(),
                 },
             }),
+            confidence: 80,
         }];
 
         assert_eq!(results, expected);
@@ -1347,6 +1482,7 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 40,
             }];
 
             assert_eq!(results, expected);
@@ -1371,6 +1507,7 @@ mod link_parsing_tests {
                         text: ":400".to_string(),
                     },
                 }),
+                confidence: 80,
             }];
 
             assert_eq!(results, expected);
@@ -1386,6 +1523,7 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 40,
             }];
 
             assert_eq!(results, expected);
@@ -1410,6 +1548,7 @@ mod link_parsing_tests {
                         text: ":400".to_string(),
                     },
                 }),
+                confidence: 80,
             }];
 
             assert_eq!(results, expected);
@@ -1440,6 +1579,7 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 40,
             }];
 
             assert_eq!(results, expected);
@@ -1455,18 +1595,19 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 40,
             }];
 
             assert_eq!(results, expected);
 
             // Test no links starting with ? in query strings
             let line = "http://foo.com/?bar=/a/b&baz=c";
-            let results = detect_links(&line, *os);
+            let results = detect_links(line, *os);
             assert!(!results.iter().any(|link| link.path.text.starts_with('?')));
 
             // Test no links starting with ? in Windows-style query strings
             let line = "http://foo.com/?bar=a:\\b&baz=c";
-            let results = detect_links(&line, *os);
+            let results = detect_links(line, *os);
             assert!(!results.iter().any(|link| link.path.text.starts_with('?')));
         }
     }
@@ -1484,6 +1625,7 @@ mod link_parsing_tests {
             },
             prefix: None,
             suffix: None,
+            confidence: 20,
         }];
 
         assert_eq!(results, expected);
@@ -1499,6 +1641,7 @@ mod link_parsing_tests {
             },
             prefix: None,
             suffix: None,
+            confidence: 20,
         }];
 
         assert_eq!(results, expected);
@@ -1515,6 +1658,7 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 20,
             },
             crate::ParsedLink {
                 path: LinkPartialRange {
@@ -1523,6 +1667,7 @@ mod link_parsing_tests {
                 },
                 prefix: None,
                 suffix: None,
+                confidence: 20,
             },
         ];
 
@@ -1545,7 +1690,7 @@ mod link_parsing_tests {
             }
 
             let line = format!(" {} {} {} ", link1.link, link2.link, link3.link);
-            let results = detect_links(&line, OperatingSystem::Linux);
+            let results = without_confidence(detect_links(&line, OperatingSystem::Linux));
 
             let detected_link_1 = crate::ParsedLink {
                 prefix: link1.prefix.map(|p| LinkPartialRange {
@@ -1577,6 +1722,7 @@ mod link_parsing_tests {
                         text: link1.suffix.unwrap().to_string(),
                     },
                 }),
+                confidence: 0,
             };
 
             let detected_link_2 = crate::ParsedLink {
@@ -1628,6 +1774,7 @@ mod link_parsing_tests {
                         text: link2.suffix.unwrap().to_string(),
                     },
                 }),
+                confidence: 0,
             };
 
             let detected_link_3 = crate::ParsedLink {
@@ -1677,6 +1824,7 @@ mod link_parsing_tests {
                         text: link3.suffix.unwrap().to_string(),
                     },
                 }),
+                confidence: 0,
             };
 
             let expected = vec![detected_link_1, detected_link_2, detected_link_3];
@@ -1692,4 +1840,110 @@ mod link_parsing_tests {
         let results = detect_links(line, OperatingSystem::Linux);
         assert_eq!(results, Vec::new());
     }
+
+    #[test]
+    fn test_detect_links_with_spaces() {
+        for test_link in TEST_LINKS_WITH_SPACES {
+            let line = format!("see {} here", test_link.link);
+            let results = without_confidence(detect_links(&line, OperatingSystem::Linux));
+
+            let prefix_len = test_link.prefix.map_or(0, |p| p.len());
+            let expected = vec![crate::ParsedLink {
+                path: LinkPartialRange {
+                    index: 4 + prefix_len,
+                    text: test_link.path.to_string(),
+                },
+                prefix: test_link.prefix.map(|p| LinkPartialRange {
+                    index: 4,
+                    text: p.to_string(),
+                }),
+                suffix: test_link.suffix.map(|s| {
+                    let suffix = get_link_suffix(test_link.link).expect("should have suffix");
+                    assert_eq!(suffix.suffix.text, s, "{}", test_link.link);
+                    LinkSuffix {
+                        suffix: LinkPartialRange {
+                            index: 4 + suffix.suffix.index,
+                            text: suffix.suffix.text.clone(),
+                        },
+                        ..suffix
+                    }
+                }),
+                confidence: 0,
+            }];
+
+            assert_eq!(results, expected, "{}", test_link.link);
+        }
+    }
+
+    #[test]
+    fn test_detect_links_with_spaces_ignores_numbers_in_path() {
+        let line = "\"My 2019 files/foo\", line 5";
+        let results = detect_links(line, OperatingSystem::Linux);
+
+        let expected = vec![crate::ParsedLink {
+            path: LinkPartialRange {
+                index: 1,
+                text: "My 2019 files/foo".to_string(),
+            },
+            prefix: Some(LinkPartialRange {
+                index: 0,
+                text: "\"".to_string(),
+            }),
+            suffix: Some(LinkSuffix {
+                row: Some(5),
+                col: None,
+                row_end: None,
+                col_end: None,
+                suffix: LinkPartialRange {
+                    index: 18,
+                    text: "\", line 5".to_string(),
+                },
+            }),
+            confidence: 75,
+        }];
+
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_detect_links_with_quoted_spaces_on_windows() {
+        let line = "open \"C:\\Program Files\\foo.txt\" now";
+        let results = detect_links(line, OperatingSystem::Windows);
+
+        let expected = vec![crate::ParsedLink {
+            path: LinkPartialRange {
+                index: 6,
+                text: "C:\\Program Files\\foo.txt".to_string(),
+            },
+            prefix: Some(LinkPartialRange {
+                index: 5,
+                text: "\"".to_string(),
+            }),
+            suffix: None,
+            confidence: 80,
+        }];
+
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_detect_links_paths_are_whole_words() {
+        let cases = [
+            ("foo:1bar:2", vec!["foo", "foo:1bar"]),
+            ("foo 1/bar.rs:3", vec!["foo", "1/bar.rs"]),
+            (
+                "at foo (http://localhost:3000/static/js/main.js:10:5)",
+                vec![
+                    "(http://localhost",
+                    "(http://localhost:3000/static/js/main.js",
+                ],
+            ),
+        ];
+
+        for (line, expected) in cases {
+            let results = detect_links(line, OperatingSystem::Linux);
+            let paths: Vec<&str> = results.iter().map(|l| l.path.text.as_str()).collect();
+            assert_eq!(paths, expected, "{}", line);
+        }
+    }
 }
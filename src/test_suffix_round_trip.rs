@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod suffix_round_trip_tests {
+    use crate::format::{Location, SUFFIX_TEMPLATES, format_with_template};
+    use crate::{LinkPartialRange, LinkSuffix, OperatingSystem, detect_links, get_link_suffix};
+    use proptest::prelude::*;
+
+    /// Text that is put before the link, similar to what compilers and test runners print.
+    const NOISE_BEFORE: &[&str] = &["", "error: ", "  --> ", "at ", "File ", "warning in "];
+
+    /// Text that is put after the link.
+    const NOISE_AFTER: &[&str] = &["", " failed", ": error", " in function main", ".", ","];
+
+    fn path_strategy() -> impl Strategy<Value = String> {
+        (
+            prop::sample::select(vec!["", "./", "../", "/", "~/"]),
+            prop::collection::vec("[a-z_][a-z0-9_]{0,8}", 1..4),
+            prop::sample::select(vec!["", ".rs", ".py", ".test.ts"]),
+        )
+            .prop_map(|(prefix, segments, extension)| {
+                format!("{}{}{}", prefix, segments.join("/"), extension)
+            })
+    }
+
+    fn location_strategy() -> impl Strategy<Value = (String, Location)> {
+        (
+            prop::sample::select(SUFFIX_TEMPLATES.clone()),
+            path_strategy(),
+            1..100_000u32,
+            1..1_000u32,
+            1..100_000u32,
+            1..1_000u32,
+        )
+            .prop_map(|(template, path, row, col, row_end, col_end)| {
+                let location = Location {
+                    path,
+                    row,
+                    col: template.contains("{col}").then_some(col),
+                    row_end: template.contains("{row_end}").then_some(row_end),
+                    col_end: template.contains("{col_end}").then_some(col_end),
+                };
+                (template, location)
+            })
+    }
+
+    fn expected_suffix(location: &Location, index: usize, text: &str) -> LinkSuffix {
+        LinkSuffix {
+            row: Some(location.row),
+            col: location.col,
+            row_end: location.row_end,
+            col_end: location.col_end,
+            suffix: LinkPartialRange {
+                index,
+                text: text.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_templates_are_unique() {
+        let mut templates = SUFFIX_TEMPLATES.clone();
+        templates.sort();
+        templates.dedup();
+        assert_eq!(templates.len(), SUFFIX_TEMPLATES.len());
+    }
+
+    proptest! {
+        #[test]
+        fn test_get_link_suffix_round_trip(
+            (template, location) in location_strategy(),
+            non_breaking_spaces: bool,
+        ) {
+            let mut formatted = format_with_template(&template, &location).unwrap();
+            let path_end = formatted.find(&location.path).unwrap() + location.path.len();
+            if non_breaking_spaces {
+                formatted = format!(
+                    "{}{}",
+                    &formatted[..path_end],
+                    formatted[path_end..].replace(' ', "\u{00A0}")
+                );
+            }
+
+            let suffix = get_link_suffix(&formatted);
+            prop_assert_eq!(
+                suffix,
+                Some(expected_suffix(&location, path_end, &formatted[path_end..])),
+                "{}",
+                formatted
+            );
+        }
+
+        #[test]
+        fn test_detect_links_round_trip(
+            (template, location) in location_strategy(),
+            before in prop::sample::select(NOISE_BEFORE),
+            after in prop::sample::select(NOISE_AFTER),
+        ) {
+            let formatted = format_with_template(&template, &location).unwrap();
+            let line = format!("{}{}{}", before, formatted, after);
+            let results = detect_links(&line, OperatingSystem::Linux);
+
+            prop_assert_eq!(results.len(), 1, "{} {:?}", line, results);
+            let link = &results[0];
+            let path_index = before.len() + formatted.find(&location.path).unwrap();
+            let path_end = path_index + location.path.len();
+            prop_assert_eq!(
+                &link.path,
+                &LinkPartialRange {
+                    index: path_index,
+                    text: location.path.clone(),
+                },
+                "{}",
+                line
+            );
+            prop_assert_eq!(
+                link.suffix.as_ref(),
+                Some(&expected_suffix(
+                    &location,
+                    path_end,
+                    &line[path_end..before.len() + formatted.len()]
+                )),
+                "{}",
+                line
+            );
+        }
+    }
+}